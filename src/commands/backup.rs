@@ -0,0 +1,102 @@
+use crate::utils::rcon::{RconClient, resolve_rcon_config};
+use crate::utils::world::{backup_path, level_name, list_backups};
+use clap::{Arg, Command};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::fs::{self, File};
+use std::path::Path;
+use std::time::SystemTime;
+use tracing::{info, warn};
+
+/// Build the backup subcommand definition
+pub fn command() -> Command {
+    Command::new("backup")
+        .about("Archive the world directory into backups/<timestamp>.tar.gz")
+        .arg(
+            Arg::new("no_save_flush")
+                .long("no-save-flush")
+                .help("Skip the RCON save-off/save-all/save-on around the archive")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("keep")
+                .long("keep")
+                .value_name("N")
+                .help("Keep only the N newest backups, deleting older ones")
+                .value_parser(clap::value_parser!(u32).range(1..)),
+        )
+}
+
+/// Execute the backup subcommand
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let world = level_name();
+    if !Path::new(&world).is_dir() {
+        return Err(format!("World directory '{}' not found", world).into());
+    }
+
+    // Pause autosaves and flush pending chunks so the archive is consistent
+    let mut rcon = None;
+    if !matches.get_flag("no_save_flush") {
+        let (host, port, password) = resolve_rcon_config("server.properties");
+        match RconClient::connect(&host, port, &password).await {
+            Ok(mut client) => {
+                client.cmd("save-off").await?;
+                // Saving is off from here on, so every exit path must turn it back on
+                let flushed = client.cmd("save-all flush").await;
+                rcon = Some(client);
+                if let Err(e) = flushed {
+                    resume_saves(rcon).await;
+                    return Err(e.into());
+                }
+                info!("Flushed world saves via RCON.");
+            }
+            Err(e) => info!("RCON unavailable ({}), archiving without a save flush.", e),
+        }
+    }
+
+    let path = backup_path(SystemTime::now());
+    let result = archive(&world, &path);
+
+    // Always turn autosave back on, even if archiving failed
+    resume_saves(rcon).await;
+    if let Err(e) = result {
+        let _ = fs::remove_file(&path);
+        return Err(e);
+    }
+    info!("Backed up '{}' to {}", world, path.display());
+
+    if let Some(keep) = matches.get_one::<u32>("keep") {
+        let backups = list_backups()?;
+        let excess = backups.len().saturating_sub(*keep as usize);
+        for old in backups.iter().take(excess) {
+            fs::remove_file(old)?;
+            info!("Deleted old backup: {}", old.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Send `save-on` over the connection that paused autosaves, if there is one
+async fn resume_saves(rcon: Option<RconClient>) {
+    if let Some(mut client) = rcon
+        && let Err(e) = client.cmd("save-on").await
+    {
+        warn!(
+            "could not re-enable saving via RCON ({}); run 'save-on'.",
+            e
+        );
+    }
+}
+
+/// Write `world` into a gzipped tarball at `path`, rooted at the world's name
+fn archive(world: &str, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let file = File::create(path)?;
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    builder.append_dir_all(world, world)?;
+    builder.into_inner()?.finish()?;
+    Ok(())
+}
@@ -0,0 +1,33 @@
+use clap::{Arg, Command};
+use clap_complete::{Shell, generate};
+use std::io::Write;
+
+/// Build the completions subcommand definition
+pub fn command() -> Command {
+    Command::new("completions")
+        .about("Print a shell completion script to stdout")
+        .hide(true)
+        .arg(
+            Arg::new("shell")
+                .value_name("SHELL")
+                .help("Shell to generate completions for")
+                .required(true)
+                .value_parser(["bash", "zsh", "fish", "powershell"]),
+        )
+}
+
+/// Execute the completions subcommand
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let shell: Shell = matches.get_one::<String>("shell").unwrap().parse()?;
+    // Render into a buffer so a closed pipe surfaces as an error instead of a panic
+    let mut script = Vec::new();
+    // Register completions for the installed binary name, not the display name
+    generate(
+        shell,
+        &mut crate::cli(),
+        env!("CARGO_PKG_NAME"),
+        &mut script,
+    );
+    std::io::stdout().write_all(&script)?;
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use clap::Command;
+
+pub mod set_default;
+pub mod validate;
+
+pub fn command() -> Command {
+    Command::new("config")
+        .about("Inspect and check mc.toml, and set user-wide defaults")
+        .subcommand(validate::command())
+        .subcommand(set_default::command())
+}
+
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    match matches.subcommand() {
+        Some(("validate", sub_matches)) => validate::execute(sub_matches).await?,
+        Some(("set-default", sub_matches)) => set_default::execute(sub_matches).await?,
+        _ => {
+            println!("Use a subcommand, e.g., 'config validate --help'.");
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use crate::utils::global_config::{GlobalConfig, KEYS};
+use clap::{Arg, Command};
+use tracing::info;
+
+pub fn command() -> Command {
+    Command::new("set-default")
+        .about("Set a user-wide default in ~/.config/mc-cli/config.toml")
+        .arg(
+            Arg::new("key")
+                .help("Setting to change")
+                .required(true)
+                .value_parser(KEYS.to_vec())
+                .index(1),
+        )
+        .arg(
+            Arg::new("value")
+                .help("New value; an empty string removes the setting")
+                .required(true)
+                .allow_hyphen_values(true)
+                .index(2),
+        )
+}
+
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let key = matches.get_one::<String>("key").unwrap();
+    let value = matches.get_one::<String>("value").unwrap();
+
+    let mut config = GlobalConfig::load()?;
+    config.set(key, value)?;
+    let path = config.save()?;
+    if value.trim().is_empty() {
+        info!("Removed {} from {}", key, path.display());
+    } else if key == "modrinth_token" {
+        info!("Saved {} to {}", key, path.display());
+    } else {
+        info!("{} = {:?} saved to {}", key, value.trim(), path.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use crate::libs::modrinth::{CONCURRENT_LOOKUPS, ModrinthClient};
+use crate::utils::config_file::{McConfig, ProjectKind};
+use crate::utils::network;
+use clap::Command;
+use futures::stream::{self, StreamExt};
+use std::path::Path;
+use tracing::info;
+
+pub fn command() -> Command {
+    Command::new("validate").about("Check mc.toml for mistakes without starting the server")
+}
+
+pub async fn execute(_matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let config = McConfig::load().map_err(|e| format!("mc.toml: {}", e))?;
+
+    let mut problems = check_local(&config);
+    if network::is_offline() {
+        info!("Skipping the Modrinth slug check (--offline).");
+    } else {
+        problems.extend(check_projects(&config).await?);
+    }
+
+    if problems.is_empty() {
+        println!("mc.toml is valid.");
+        return Ok(());
+    }
+    for problem in problems.iter() {
+        eprintln!("{}", problem);
+    }
+    Err(format!("Found {} problem(s) in mc.toml.", problems.len()).into())
+}
+
+/// Checks that need no network access
+fn check_local(config: &McConfig) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    for (key, value) in [
+        ("versions.mc_version", &config.versions.mc_version),
+        ("versions.fabric_version", &config.versions.fabric_version),
+        ("versions.mc_cli_version", &config.versions.mc_cli_version),
+    ] {
+        // Paper has no loader, so its fabric_version is legitimately empty
+        let unused = key == "versions.fabric_version" && !config.versions.is_modded();
+        if value.trim().is_empty() && !unused {
+            problems.push(format!("{}: must not be empty", key));
+        }
+    }
+
+    if config.console.launch_cmd.is_empty() {
+        problems.push("console.launch_cmd: must not be empty".to_string());
+    } else {
+        match config.console.jar() {
+            Some(jar) if !Path::new(jar).exists() => problems.push(format!(
+                "console.launch_cmd: server jar '{}' does not exist",
+                jar
+            )),
+            Some(_) => {}
+            None => problems.push("console.launch_cmd: no server jar referenced".to_string()),
+        }
+    }
+
+    problems
+}
+
+/// Check that every tracked project slug exists on Modrinth
+async fn check_projects(config: &McConfig) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut entries: Vec<(ProjectKind, String)> = Vec::new();
+    for kind in [
+        ProjectKind::Mod,
+        ProjectKind::Datapack,
+        ProjectKind::Resourcepack,
+    ] {
+        let mut slugs: Vec<String> = config.installed(kind).keys().cloned().collect();
+        slugs.sort();
+        entries.extend(slugs.into_iter().map(|s| (kind, s)));
+    }
+
+    let client = ModrinthClient::new()?;
+    let problems: Vec<Option<String>> = stream::iter(entries)
+        .map(|(kind, slug)| {
+            let client = &client;
+            async move {
+                match client.find_project(&slug).await {
+                    Ok(Some(_)) => None,
+                    Ok(None) => Some(format!(
+                        "{}.{}: project not found on Modrinth",
+                        kind.dir(),
+                        slug
+                    )),
+                    Err(e) => Some(format!("{}.{}: could not check ({})", kind.dir(), slug, e)),
+                }
+            }
+        })
+        .buffered(CONCURRENT_LOOKUPS)
+        .collect()
+        .await;
+    Ok(problems.into_iter().flatten().collect())
+}
@@ -1,42 +1,131 @@
-use clap::Command;
+use clap::{Arg, Command};
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
 use std::{
-    io::{self, Write},
-    path::PathBuf,
+    fs,
+    io::{self, IsTerminal},
+    time::Duration,
 };
 
-use crate::utils::mc_server_props::ServerProperties;
-use crate::utils::rcon::RconClient;
+use crate::utils::mc_format;
+use crate::utils::paths;
+use crate::utils::rcon::{RconClient, resolve_rcon_config};
+use tracing::info;
 
 /// Build the console subcommand definition
 pub fn command() -> Command {
-    Command::new("console").about("Interact with the Minecraft server console via RCON")
+    Command::new("console")
+        .about("Interact with the Minecraft server console via RCON")
+        .arg(
+            Arg::new("timeout")
+                .long("timeout")
+                .short('t')
+                .value_name("SECS")
+                .help("Seconds to wait for an RCON response before giving up")
+                .value_parser(clap::value_parser!(u64))
+                .default_value("5"),
+        )
+        .arg(
+            Arg::new("cmd")
+                .long("cmd")
+                .short('c')
+                .value_name("COMMAND")
+                .help("Run a command and exit instead of starting the console; repeatable")
+                .action(clap::ArgAction::Append),
+        )
+        .arg(Arg::new("host").long("host").value_name("HOST").help(
+            "RCON host, overriding server.properties (password then defaults to $RCON_PASSWORD)",
+        ))
+        .arg(
+            Arg::new("port")
+                .long("port")
+                .short('p')
+                .value_name("PORT")
+                .help("RCON port, overriding server.properties")
+                .value_parser(clap::value_parser!(u16).range(1..)),
+        )
+        .arg(
+            Arg::new("password")
+                .long("password")
+                .value_name("PASSWORD")
+                .help("RCON password, overriding server.properties and $RCON_PASSWORD"),
+        )
+        .arg(
+            Arg::new("raw")
+                .long("raw")
+                .help("Print replies as sent, keeping Minecraft § formatting codes")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+/// Make a reply readable: ANSI colors on a terminal, plain text otherwise
+pub fn render_reply(reply: &str, raw: bool) -> String {
+    if raw {
+        reply.to_string()
+    } else if io::stdout().is_terminal() {
+        mc_format::to_ansi(reply)
+    } else {
+        mc_format::strip_codes(reply)
+    }
 }
 
 /// Execute the console subcommand
-pub async fn execute(_: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
-    // Resolve config from args or server.properties
-    let (host, port, password) = get_rcon_config().await?;
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let timeout = Duration::from_secs(*matches.get_one::<u64>("timeout").unwrap());
+    let raw = matches.get_flag("raw");
+
+    // Resolve config from flags, RCON_* env vars or server.properties
+    let (mut host, mut port, mut password) = resolve_rcon_config("server.properties");
+    if let Some(h) = matches.get_one::<String>("host") {
+        // A remote server's password is unrelated to the local server.properties
+        host = h.clone();
+        password = std::env::var("RCON_PASSWORD").unwrap_or_default();
+    }
+    if let Some(p) = matches.get_one::<u16>("port") {
+        port = *p;
+    }
+    if let Some(pw) = matches.get_one::<String>("password") {
+        password = pw.clone();
+    }
+
+    let scripted: Option<Vec<&String>> = matches.get_many::<String>("cmd").map(|c| c.collect());
+    if scripted.is_none() {
+        info!("Connecting to RCON at {}:{} ...", host, port);
+    }
+    let mut client = RconClient::connect_with_timeout(&host, port, &password, timeout)
+        .await
+        .map_err(|e| format!("Failed to connect/authenticate: {}", e))?;
 
-    println!("Connecting to RCON at {}:{} ...", host, port);
-    let mut client = match RconClient::connect(&host, port, &password).await {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("Failed to connect/authenticate: {}", e);
-            return Err(e);
+    // Scripted mode: run each command in order, print its reply, and exit
+    if let Some(cmds) = scripted {
+        for cmd in cmds {
+            let reply = client.cmd(cmd).await?;
+            if !reply.is_empty() {
+                println!("{}", render_reply(&reply, raw));
+            }
         }
-    };
+        return Ok(());
+    }
 
-    println!("Logged in. Type 'Q' or Ctrl-D to exit.");
+    let mut editor = DefaultEditor::new()?;
+    let history = paths::state_dir().map(|d| d.join("console_history"));
+    if let Some(path) = history.as_ref() {
+        // A missing history file just means this is the first session
+        let _ = editor.load_history(path);
+    }
+
+    info!("Logged in. Type 'Q' or Ctrl-D to exit.");
     loop {
-        print!("> ");
-        io::stdout().flush()?;
-        let mut input = String::new();
-        let read = io::stdin().read_line(&mut input)?;
-        if read == 0 {
-            // EOF
-            println!("Exiting console.");
-            break;
-        }
+        let input = match editor.readline("> ") {
+            Ok(line) => line,
+            // Ctrl-C cancels the current line rather than leaving the console
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => {
+                info!("Exiting console.");
+                break;
+            }
+            Err(e) => return Err(e.into()),
+        };
         let cmd = input.trim();
         if cmd.is_empty() {
             continue;
@@ -44,9 +133,10 @@ pub async fn execute(_: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Err
         if cmd.eq_ignore_ascii_case("Q") {
             break;
         }
+        let _ = editor.add_history_entry(cmd);
 
         match client.cmd(cmd).await {
-            Ok(reply) => println!("{}", reply),
+            Ok(reply) => println!("{}", render_reply(&reply, raw)),
             Err(e) => eprintln!("Error: {}", e),
         }
 
@@ -56,35 +146,12 @@ pub async fn execute(_: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Err
         }
     }
 
-    Ok(())
-}
-
-async fn get_rcon_config() -> Result<(String, u16, String), Box<dyn std::error::Error>> {
-    // Defaults
-    let mut _host = String::new();
-    let mut _port = String::new();
-    let mut password = String::new();
-
-    // Server properties fallback
-    let props = ServerProperties::from_file(PathBuf::from("server.properties"));
-    if let Ok(p) = props {
-        let host = p
-            .get("rcon.host")
-            .or_else(|| p.get("rcon_host"))
-            .unwrap_or_else(|| "127.0.0.1".to_string());
-        let port = p
-            .get("rcon.port")
-            .or_else(|| p.get("rcon_port"))
-            .unwrap_or_else(|| "25575".to_string());
-        password = p
-            .get("rcon.password")
-            .or_else(|| p.get("rcon_password"))
-            .unwrap_or_default();
-        Ok((host, port.parse::<u16>().unwrap_or(25575), password))
-    } else {
-        // If server.properties missing, apply hard defaults
-        let host = "127.0.0.1".to_string();
-        let port = "25575".to_string();
-        Ok((host, port.parse::<u16>().unwrap_or(25575), password))
+    if let Some(path) = history.as_ref() {
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let _ = editor.save_history(path);
     }
+
+    Ok(())
 }
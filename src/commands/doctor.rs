@@ -0,0 +1,228 @@
+use crate::utils::config_file::McConfig;
+use crate::utils::java;
+use crate::utils::mc_server_props::ServerProperties;
+use crate::utils::pid_file;
+use clap::Command;
+use std::net::TcpListener;
+use std::path::Path;
+
+/// Password `init` writes; fine locally, worth changing before exposing RCON
+const DEFAULT_RCON_PASSWORD: &str = "changeme";
+
+/// Build the doctor subcommand definition
+pub fn command() -> Command {
+    Command::new("doctor")
+        .about("Check Java, the server jar, the EULA, the server port and RCON settings")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Status::Pass => "ok",
+            Status::Warn => "warn",
+            Status::Fail => "FAIL",
+        }
+    }
+}
+
+/// One line of the report
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+}
+
+impl Check {
+    fn new(name: &'static str, status: Status, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status,
+            detail: detail.into(),
+        }
+    }
+}
+
+/// Execute the doctor subcommand
+pub async fn execute(_matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let mut checks = Vec::new();
+    match McConfig::load() {
+        Ok(config) => {
+            checks.push(Check::new("mc.toml", Status::Pass, "loaded"));
+            checks.push(check_java(&config));
+            checks.push(check_jar(&config));
+        }
+        Err(e) => checks.push(Check::new("mc.toml", Status::Fail, e.to_string())),
+    }
+    checks.push(check_eula());
+
+    let props = ServerProperties::from_file("server.properties").ok();
+    match &props {
+        Some(props) => {
+            checks.push(check_port(props));
+            checks.push(check_rcon(props));
+        }
+        None => checks.push(Check::new(
+            "server.properties",
+            Status::Warn,
+            "not found; start the server once to generate it",
+        )),
+    }
+
+    for check in checks.iter() {
+        println!(
+            "[{:>4}] {:<18} {}",
+            check.status.label(),
+            check.name,
+            check.detail
+        );
+    }
+    let failed = checks.iter().filter(|c| c.status == Status::Fail).count();
+    if failed > 0 {
+        return Err(format!("{} check(s) failed", failed).into());
+    }
+    Ok(())
+}
+
+/// Java runs and is new enough for the configured Minecraft version
+fn check_java(config: &McConfig) -> Check {
+    // A wrapper script in launch_cmd still needs a JVM on PATH (or java_path)
+    let launch = config.console.command(None);
+    let java = match launch.first() {
+        Some(program) if java::is_java(program) => program.clone(),
+        _ => config
+            .console
+            .java_path
+            .clone()
+            .unwrap_or_else(|| String::from("java")),
+    };
+    let mc_version = &config.versions.mc_version;
+    match (java::major_version(&java), java::required_for(mc_version)) {
+        (None, _) => Check::new(
+            "Java",
+            Status::Fail,
+            format!("could not run '{} -version'; is Java installed?", java),
+        ),
+        (Some(found), Some(required)) if found < required => Check::new(
+            "Java",
+            Status::Fail,
+            format!(
+                "'{}' is Java {}, but Minecraft {} needs Java {}",
+                java, found, mc_version, required
+            ),
+        ),
+        (Some(found), Some(required)) => Check::new(
+            "Java",
+            Status::Pass,
+            format!(
+                "Java {} (Minecraft {} needs {}+)",
+                found, mc_version, required
+            ),
+        ),
+        (Some(found), None) => Check::new(
+            "Java",
+            Status::Warn,
+            format!(
+                "Java {}; cannot tell what Minecraft {} needs",
+                found, mc_version
+            ),
+        ),
+    }
+}
+
+/// The jar launch_cmd points at exists
+fn check_jar(config: &McConfig) -> Check {
+    match config.console.jar() {
+        Some(jar) if Path::new(jar).is_file() => Check::new("Server jar", Status::Pass, jar),
+        Some(jar) => Check::new("Server jar", Status::Fail, format!("'{}' not found", jar)),
+        None => Check::new(
+            "Server jar",
+            Status::Fail,
+            "console.launch_cmd references no jar",
+        ),
+    }
+}
+
+/// eula.txt exists and accepts the Minecraft EULA
+fn check_eula() -> Check {
+    match ServerProperties::from_file("eula.txt") {
+        Ok(eula) if eula.get("eula").as_deref() == Some("true") => {
+            Check::new("EULA", Status::Pass, "accepted in eula.txt")
+        }
+        Ok(_) => Check::new(
+            "EULA",
+            Status::Fail,
+            "eula.txt does not set eula=true; the server will not start",
+        ),
+        Err(_) => Check::new(
+            "EULA",
+            Status::Fail,
+            "eula.txt not found; run init or accept https://aka.ms/MinecraftEULA",
+        ),
+    }
+}
+
+/// The game port can be bound, unless our own server already holds it
+fn check_port(props: &ServerProperties) -> Check {
+    let (ip, port) = match props.bind_address() {
+        Ok(addr) => addr,
+        Err(e) => return Check::new("Server port", Status::Fail, e),
+    };
+
+    match TcpListener::bind((ip.as_str(), port)) {
+        Ok(_) => Check::new("Server port", Status::Pass, format!("{} is free", port)),
+        Err(_) if pid_file::running_pid().is_some() => Check::new(
+            "Server port",
+            Status::Pass,
+            format!("{} is in use by the running server", port),
+        ),
+        Err(e) => Check::new(
+            "Server port",
+            Status::Fail,
+            format!("cannot bind {}:{} ({})", ip, port, e),
+        ),
+    }
+}
+
+/// RCON is enabled with a usable password and its own port
+fn check_rcon(props: &ServerProperties) -> Check {
+    if props.get("enable-rcon").as_deref() != Some("true") {
+        return Check::new(
+            "RCON",
+            Status::Warn,
+            "disabled; console, player lists in status and backup's save flush need enable-rcon=true",
+        );
+    }
+    let password = props.get("rcon.password").unwrap_or_default();
+    let rcon_port = props.get("rcon.port");
+    if password.is_empty() {
+        Check::new(
+            "RCON",
+            Status::Fail,
+            "enabled without rcon.password; the server will not start RCON",
+        )
+    } else if rcon_port.is_some() && rcon_port == props.get("server-port") {
+        Check::new("RCON", Status::Fail, "rcon.port is the same as server-port")
+    } else if password == DEFAULT_RCON_PASSWORD {
+        Check::new(
+            "RCON",
+            Status::Warn,
+            "using the default password; change rcon.password before exposing the port",
+        )
+    } else {
+        Check::new(
+            "RCON",
+            Status::Pass,
+            format!(
+                "enabled on port {}",
+                rcon_port.as_deref().unwrap_or("25575")
+            ),
+        )
+    }
+}
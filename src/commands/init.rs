@@ -1,7 +1,15 @@
-use crate::libs::fabric::{FabricClient, GameVersion, InstallerVersion, LoaderVersion};
-use crate::utils::config_file::{Console as ConsoleConfig, McConfig, Versions};
-use crate::utils::mc_server_props::ServerProperties;
-use crate::utils::runner::run_cmd;
+use crate::libs::fabric::FabricClient;
+use crate::libs::paper::{Build as PaperBuild, PaperClient};
+use crate::libs::quilt::QuiltClient;
+use crate::utils::checksum::verify_sha256;
+use crate::utils::config_file::{
+    Console as ConsoleConfig, McConfig, NO_LOADER, SERVER_TYPES, Versions,
+};
+use crate::utils::download;
+use crate::utils::global_config::GlobalConfig;
+use crate::utils::java;
+use crate::utils::mc_server_props::{PropType, ServerProperties};
+use clap::parser::ValueSource;
 use clap::{Arg, Command};
 use crossterm::{
     event::{self, Event, KeyCode},
@@ -13,8 +21,18 @@ use ratatui::{
     style::{Color, Modifier, Style},
     widgets::{Block, Borders, List, ListItem, Paragraph},
 };
-use std::io::{self};
-use std::path::PathBuf;
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command as SysCommand, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// How long the first server start may take to generate its files
+const INITIAL_START_TIMEOUT: Duration = Duration::from_secs(120);
+/// Lines of server stderr kept for error messages
+const STDERR_TAIL_LINES: usize = 20;
 
 /// Build the init subcommand definition
 pub fn command() -> Command {
@@ -29,100 +47,592 @@ pub fn command() -> Command {
                 .required(false)
                 .default_value("my-minecraft-project"),
         )
+        .arg(
+            Arg::new("server_type")
+                .long("server-type")
+                .value_name("TYPE")
+                .help("Server software to install")
+                .value_parser(SERVER_TYPES.to_vec())
+                .default_value("fabric"),
+        )
+        .arg(
+            Arg::new("loader_type")
+                .long("loader-type")
+                .value_name("LOADER")
+                .help("Mod loader to install on a fabric-type server")
+                .value_parser(["fabric", "quilt"])
+                .default_value("fabric"),
+        )
+        .arg(
+            Arg::new("mc_version")
+                .long("mc-version")
+                .value_name("VERSION")
+                .help("Minecraft version to use (skips the interactive selector)")
+                .required(false),
+        )
+        .arg(
+            Arg::new("loader_version")
+                .long("loader-version")
+                .value_name("VERSION")
+                .help("Loader version to use (skips the interactive selector)")
+                .required(false),
+        )
+        .arg(
+            Arg::new("installer_version")
+                .long("installer-version")
+                .value_name("VERSION")
+                .help("Installer version to use (skips the interactive selector)")
+                .required(false),
+        )
+        .arg(
+            Arg::new("yes")
+                .long("yes")
+                .short('y')
+                .help("Run without prompting; omitted versions default to latest stable")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("memory")
+                .long("memory")
+                .value_name("SIZE")
+                .help("Maximum JVM heap (-Xmx), e.g. 4G or 2048M")
+                .value_parser(java::parse_memory)
+                .default_value("2G"),
+        )
+        .arg(
+            Arg::new("min_memory")
+                .long("min-memory")
+                .value_name("SIZE")
+                .help("Initial JVM heap (-Xms), e.g. 1G; omitted by default")
+                .value_parser(java::parse_memory),
+        )
+        .arg(
+            Arg::new("jvm_flags")
+                .long("jvm-flags")
+                .value_name("FLAGS")
+                .help("Extra space-separated JVM flags placed before -jar, e.g. \"-XX:+UseG1GC\"")
+                .allow_hyphen_values(true),
+        )
+        .arg(
+            Arg::new("java")
+                .long("java")
+                .value_name("PATH")
+                .help("Java binary to use, stored as console.java_path"),
+        )
+        .arg(
+            Arg::new("strict")
+                .long("strict")
+                .help("Fail instead of warning when Java is missing or too old")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("accept_eula")
+                .long("accept-eula")
+                .help("Accept the Minecraft EULA (https://aka.ms/MinecraftEULA); required when headless")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("refresh")
+                .long("refresh")
+                .help("Ignore cached Fabric version lists and fetch fresh ones")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("snapshots")
+                .long("snapshots")
+                .help("Include snapshots and pre-releases in the game version selector")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("force")
+                .long("force")
+                .help("Overwrite an existing mc.toml without asking")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("no_launch")
+                .long("no-launch")
+                .help("Only write mc.toml; skip the first server start, server.properties and eula.txt")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("download")
+                .long("download")
+                .help("With --no-launch, still download the server jar")
+                .action(clap::ArgAction::SetTrue)
+                .requires("no_launch"),
+        )
+        .arg(
+            Arg::new("props_template")
+                .long("props-template")
+                .value_name("PATH")
+                .help("Properties file merged over the generated server.properties defaults")
+                .conflicts_with("no_launch"),
+        )
 }
 
 /// Execute the init subcommand
 pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
     let project_name = matches.get_one::<String>("name").unwrap();
-    println!("Initializing new Minecraft project: {}", project_name);
-
-    // Interactive selection for Game, Loader, and Installer versions using Ratatui
-    let client = FabricClient::new()?;
-    let game_versions: Vec<GameVersion> = client.get_game_versions().await?;
-    let loader_versions: Vec<LoaderVersion> = client.get_loader_versions().await?;
-    let installer_versions: Vec<InstallerVersion> = client.get_installer_versions().await?;
-
-    let game_idx = select_with_ratatui(
-        "Select Game Version",
-        &game_versions
-            .iter()
-            .map(|g| format!("{}{}", g.version, if g.stable { " (stable)" } else { "" }))
-            .collect::<Vec<_>>(),
-    )?;
-    let loader_idx = select_with_ratatui(
-        "Select Loader Version",
-        &loader_versions
-            .iter()
-            .map(|l| format!("{}{}", l.version, if l.stable { " (stable)" } else { "" }))
-            .collect::<Vec<_>>(),
-    )?;
-    let installer_idx = select_with_ratatui(
-        "Select Installer Version",
-        &installer_versions
-            .iter()
-            .map(|i| format!("{}{}", i.version, if i.stable { " (stable)" } else { "" }))
-            .collect::<Vec<_>>(),
-    )?;
-
-    let fabric_versions = FabricVersion {
-        game: game_versions[game_idx].version.clone(),
-        loader: loader_versions[loader_idx].version.clone(),
-        installer: installer_versions[installer_idx].version.clone(),
+    let mc_version = matches.get_one::<String>("mc_version");
+    let loader_version = matches.get_one::<String>("loader_version");
+    let installer_version = matches.get_one::<String>("installer_version");
+    let headless = matches.get_flag("yes")
+        || mc_version.is_some()
+        || loader_version.is_some()
+        || installer_version.is_some();
+
+    // Remember the existing project's versions so an unchanged server jar is kept
+    let existing = if McConfig::exists() {
+        if !confirm_overwrite(matches.get_flag("force"), headless)? {
+            info!("Initialization cancelled.");
+            return Ok(());
+        }
+        McConfig::load().ok().map(|c| c.versions)
+    } else {
+        None
     };
 
-    println!("Using Fabric Versions:");
-    println!("  Loader:    {}", fabric_versions.loader);
-    println!("  Game:      {}", fabric_versions.game);
-    println!("  Installer: {}", fabric_versions.installer);
+    info!("Initializing new Minecraft project: {}", project_name);
 
-    // Create configuration file via helper
-    create_config_file(project_name, &fabric_versions).await?;
+    // Flags given on the command line win; the global config replaces built-in defaults
+    let global = GlobalConfig::load().map_err(|e| format!("Global config: {}", e))?;
+    let from_cli = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+    let loader_type = match global.loader_type.as_deref() {
+        Some(loader) if !from_cli("loader_type") => loader,
+        _ => matches.get_one::<String>("loader_type").unwrap().as_str(),
+    };
+    let refresh = matches.get_flag("refresh");
+    let snapshots = matches.get_flag("snapshots");
 
-    // Download Fabric server JAR via helper
-    download_fabric_server_jar(&fabric_versions).await?;
+    // --no-launch never starts the JVM, so it needs neither the EULA nor (unless
+    // installing Quilt) Java; the jar is only fetched when asked for
+    let launch = !matches.get_flag("no_launch");
+    let download = launch || matches.get_flag("download");
+
+    // Check the template before downloading anything, so a typo fails fast
+    let props_template = matches
+        .get_one::<String>("props_template")
+        .map(|path| load_props_template(Path::new(path)))
+        .transpose()?;
+
+    // The server will not start without eula=true, so settle consent before any work
+    if launch {
+        confirm_eula(matches.get_flag("accept_eula"), headless)?;
+    }
+
+    let java_path = matches
+        .get_one::<String>("java")
+        .or(global.java_path.as_ref());
+    let java_bin = java_path.map(String::as_str).unwrap_or("java");
+    let strict = matches.get_flag("strict");
+    let memory = match global.memory.as_ref() {
+        Some(memory) if !from_cli("memory") => memory,
+        _ => matches.get_one::<String>("memory").unwrap(),
+    };
+    let min_memory = matches
+        .get_one::<String>("min_memory")
+        .or(global.min_memory.as_ref());
+    let jvm_flags: Vec<String> = matches
+        .get_one::<String>("jvm_flags")
+        .or(global.jvm_flags.as_ref())
+        .map(|f| f.split_whitespace().map(String::from).collect())
+        .unwrap_or_default();
+
+    let versions = if matches.get_one::<String>("server_type").unwrap() == "paper" {
+        if loader_version.is_some() || installer_version.is_some() {
+            return Err("--loader-version and --installer-version do not apply to Paper".into());
+        }
+        let Some((game, build)) = resolve_paper_version(mc_version, headless, snapshots).await?
+        else {
+            info!("Initialization cancelled.");
+            return Ok(());
+        };
+        if launch {
+            java::check(java_bin, &game, strict)?;
+        }
+
+        info!("Using Paper Versions:");
+        info!("  Game:  {}", game);
+        info!("  Build: {}", build.build);
+
+        let versions = Versions {
+            mc_version: game,
+            fabric_version: String::new(),
+            mc_cli_version: String::from("0.1.0"),
+            loader_type: String::from(NO_LOADER),
+            server_type: String::from("paper"),
+        };
+        create_config_file(
+            project_name,
+            &versions,
+            memory,
+            min_memory,
+            &jvm_flags,
+            java_path,
+        )
+        .await?;
+        if !download {
+            info!("Skipping the server jar download (--no-launch)");
+        } else if jar_is_current(existing.as_ref(), &versions) {
+            info!(
+                "Keeping existing {} (versions unchanged)",
+                server_jar(&versions.loader_type)
+            );
+        } else {
+            download_paper_server_jar(&versions.mc_version, &build).await?;
+        }
+        versions
+    } else {
+        let fabric_versions = if headless {
+            fetch_fabric_versions(
+                loader_type,
+                mc_version,
+                loader_version,
+                installer_version,
+                refresh,
+            )
+            .await?
+        } else {
+            match select_fabric_versions(loader_type, refresh, snapshots).await? {
+                Some(fv) => fv,
+                None => {
+                    info!("Initialization cancelled.");
+                    return Ok(());
+                }
+            }
+        };
+        fabric_versions.ensure_resolved()?;
+        // The Quilt installer runs on Java even when the server is not launched
+        if launch || (download && fabric_versions.loader_type == "quilt") {
+            java::check(java_bin, &fabric_versions.game, strict)?;
+        }
+
+        info!("Using {} Versions:", loader_display_name(loader_type));
+        info!("  Loader:    {}", fabric_versions.loader);
+        info!("  Game:      {}", fabric_versions.game);
+        info!("  Installer: {}", fabric_versions.installer);
+
+        let versions = Versions {
+            mc_version: fabric_versions.game.clone(),
+            fabric_version: fabric_versions.loader.clone(),
+            mc_cli_version: String::from("0.1.0"),
+            loader_type: fabric_versions.loader_type.clone(),
+            server_type: String::from("fabric"),
+        };
+        create_config_file(
+            project_name,
+            &versions,
+            memory,
+            min_memory,
+            &jvm_flags,
+            java_path,
+        )
+        .await?;
+
+        // Download server JAR via helper
+        if !download {
+            info!("Skipping the server jar download (--no-launch)");
+        } else if jar_is_current(existing.as_ref(), &versions) {
+            info!(
+                "Keeping existing {} (versions unchanged)",
+                server_jar(&versions.loader_type)
+            );
+        } else if fabric_versions.loader_type == "quilt" {
+            install_quilt_server(&fabric_versions, java_bin).await?;
+        } else {
+            download_fabric_server_jar(&fabric_versions).await?;
+        }
+        versions
+    };
+
+    if !launch {
+        info!("Initialization complete. Set eula=true in eula.txt before the first run.");
+        return Ok(());
+    }
 
     // Start server once JAR is downloaded, to generate server files
-    initial_start_server().await?;
+    initial_start_server(java_bin, server_jar(&versions.loader_type)).await?;
 
     // Initial Setup
-    initial_server_setup().await?;
+    initial_server_setup(props_template.as_ref()).await?;
 
-    println!("Initialization complete.");
+    info!("Initialization complete.");
 
     Ok(())
 }
 
+/// Ask before replacing an existing mc.toml; headless runs need --force instead
+fn confirm_overwrite(force: bool, headless: bool) -> Result<bool, Box<dyn std::error::Error>> {
+    if force {
+        return Ok(true);
+    }
+    if headless {
+        return Err("mc.toml already exists; pass --force to overwrite it".into());
+    }
+    print!("mc.toml already exists. Overwrite it and re-run setup? [y/N] ");
+    io::stdout().flush()?;
+    let mut input = String::new();
+    let read = io::stdin().read_line(&mut input)?;
+    Ok(read > 0 && matches!(input.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Whether the server jar on disk was installed for exactly these versions
+fn jar_is_current(existing: Option<&Versions>, versions: &Versions) -> bool {
+    existing.is_some_and(|old| {
+        old.server_type == versions.server_type
+            && old.loader_type == versions.loader_type
+            && old.mc_version == versions.mc_version
+            && old.fabric_version == versions.fabric_version
+    }) && Path::new(server_jar(&versions.loader_type)).exists()
+}
+
+/// Require explicit acceptance of the Minecraft EULA, by flag or interactive prompt
+fn confirm_eula(accepted: bool, headless: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if accepted {
+        return Ok(());
+    }
+    if headless {
+        return Err(
+            "The Minecraft EULA must be accepted; pass --accept-eula to agree to https://aka.ms/MinecraftEULA"
+                .into(),
+        );
+    }
+
+    print!("Do you accept the Minecraft EULA (https://aka.ms/MinecraftEULA)? [y/N] ");
+    io::stdout().flush()?;
+    let mut input = String::new();
+    let read = io::stdin().read_line(&mut input)?;
+    if read > 0 && matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+        Ok(())
+    } else {
+        Err("EULA not accepted; aborting initialization".into())
+    }
+}
+
 pub struct FabricVersion {
+    /// "fabric" or "quilt"
+    pub loader_type: String,
     pub loader: String,
     pub game: String,
     pub installer: String,
 }
-/// Fetch Fabric version information
-#[allow(dead_code)]
-async fn fetch_fabric_versions() -> Result<FabricVersion, Box<dyn std::error::Error>> {
-    let client = FabricClient::new()?;
-
-    // Fetch latest stable versions
-    let loader = client.get_latest_loader().await?;
-    let game = client.get_latest_game().await?;
-    let installer = client.get_latest_installer().await?;
-
-    // latest versions variables
-    let mut lv: String = String::new();
-    let mut gv: String = String::new();
-    let mut iv: String = String::new();
-
-    if let Some(l) = loader {
-        lv = l.version.clone();
+
+impl FabricVersion {
+    /// Reject unresolved (empty) versions, which would produce an invalid download URL
+    pub fn ensure_resolved(&self) -> Result<(), Box<dyn std::error::Error>> {
+        for (what, value, flag) in [
+            ("Minecraft", &self.game, "--mc-version"),
+            ("loader", &self.loader, "--loader-version"),
+            ("installer", &self.installer, "--installer-version"),
+        ] {
+            if value.trim().is_empty() {
+                return Err(format!(
+                    "Could not resolve a {} version; pass {} to choose one",
+                    what, flag
+                )
+                .into());
+            }
+        }
+        Ok(())
     }
-    if let Some(g) = game {
-        gv = g.version.clone();
+}
+
+/// Human-readable loader name for messages
+pub fn loader_display_name(loader_type: &str) -> &'static str {
+    match loader_type {
+        "quilt" => "Quilt",
+        _ => "Fabric",
     }
-    if let Some(i) = installer {
-        iv = i.version.clone();
+}
+
+/// Selector header naming the active game version filter
+fn game_version_title(snapshots: bool) -> &'static str {
+    if snapshots {
+        "Select Game Version [all versions]"
+    } else {
+        "Select Game Version [releases only; --snapshots for all]"
     }
+}
+
+/// Jar the server is launched from for the given loader
+pub fn server_jar(loader_type: &str) -> &'static str {
+    match loader_type {
+        "quilt" => "quilt-server-launch.jar",
+        _ => "server.jar",
+    }
+}
+
+/// Interactive selection for Game, Loader, and Installer versions using Ratatui;
+/// game versions are limited to stable releases unless `snapshots` is set.
+/// `None` if the user cancelled any of the selectors
+pub async fn select_fabric_versions(
+    loader_type: &str,
+    refresh: bool,
+    snapshots: bool,
+) -> Result<Option<FabricVersion>, Box<dyn std::error::Error>> {
+    // (version, stable) pairs for each list
+    type Choices = Vec<(String, bool)>;
+    let (mut game_versions, loader_versions, installer_versions): (Choices, Choices, Choices) =
+        if loader_type == "quilt" {
+            let client = QuiltClient::new()?;
+            (
+                client
+                    .get_game_versions()
+                    .await?
+                    .into_iter()
+                    .map(|g| (g.version, g.stable))
+                    .collect(),
+                client
+                    .get_loader_versions()
+                    .await?
+                    .into_iter()
+                    .map(|l| (l.version.clone(), l.stable()))
+                    .collect(),
+                client
+                    .get_installer_versions()
+                    .await?
+                    .into_iter()
+                    .map(|i| (i.version, false))
+                    .collect(),
+            )
+        } else {
+            let client = FabricClient::new()?.with_refresh(refresh);
+            (
+                client
+                    .get_game_versions()
+                    .await?
+                    .into_iter()
+                    .map(|g| (g.version, g.stable))
+                    .collect(),
+                client
+                    .get_loader_versions()
+                    .await?
+                    .into_iter()
+                    .map(|l| (l.version, l.stable))
+                    .collect(),
+                client
+                    .get_installer_versions()
+                    .await?
+                    .into_iter()
+                    .map(|i| (i.version, i.stable))
+                    .collect(),
+            )
+        };
+
+    if !snapshots {
+        game_versions.retain(|(_, stable)| *stable);
+    }
+    let source = format!("{} meta", loader_display_name(loader_type));
+    // Stable releases may all be filtered out even when snapshots exist
+    let game_kind = if snapshots { "game" } else { "stable game" };
+    let Some(game) = select_version(
+        game_version_title(snapshots),
+        &game_versions,
+        game_kind,
+        &source,
+    )?
+    else {
+        return Ok(None);
+    };
+    let Some(loader) =
+        select_version("Select Loader Version", &loader_versions, "loader", &source)?
+    else {
+        return Ok(None);
+    };
+    let Some(installer) = select_version(
+        "Select Installer Version",
+        &installer_versions,
+        "installer",
+        &source,
+    )?
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(FabricVersion {
+        loader_type: loader_type.to_string(),
+        game,
+        loader,
+        installer,
+    }))
+}
+
+/// Pick one of `(version, stable)` choices, failing clearly if there are none
+fn select_version(
+    title: &str,
+    choices: &[(String, bool)],
+    kind: &str,
+    source: &str,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    if choices.is_empty() {
+        return Err(format!("No {} versions available from {}", kind, source).into());
+    }
+    let labels: Vec<String> = choices
+        .iter()
+        .map(|(v, stable)| format!("{}{}", v, if *stable { " (stable)" } else { "" }))
+        .collect();
+    match select_with_ratatui(title, &labels)? {
+        Some(idx) => choices
+            .get(idx)
+            .map(|(v, _)| Some(v.clone()))
+            .ok_or_else(|| format!("Selected {} version is out of range", kind).into()),
+        None => Ok(None),
+    }
+}
+
+/// Fetch loader version information, using the latest stable for any version not given
+pub async fn fetch_fabric_versions(
+    loader_type: &str,
+    game: Option<&String>,
+    loader: Option<&String>,
+    installer: Option<&String>,
+    refresh: bool,
+) -> Result<FabricVersion, Box<dyn std::error::Error>> {
+    let quilt = loader_type == "quilt";
+    let name = loader_display_name(loader_type);
+    let fabric = FabricClient::new()?.with_refresh(refresh);
+    let quilt_client = QuiltClient::new()?;
+
+    let gv = match game {
+        Some(g) => g.clone(),
+        None => if quilt {
+            quilt_client.get_latest_game().await?.map(|g| g.version)
+        } else {
+            fabric.get_latest_game().await?.map(|g| g.version)
+        }
+        .ok_or("No stable Minecraft version found; pass --mc-version to choose one")?,
+    };
+    let lv = match loader {
+        Some(l) => l.clone(),
+        None => if quilt {
+            quilt_client.get_latest_loader().await?.map(|l| l.version)
+        } else {
+            fabric.get_latest_loader().await?.map(|l| l.version)
+        }
+        .ok_or(format!(
+            "No stable {} loader version found; pass --loader-version to choose one",
+            name
+        ))?,
+    };
+    let iv = match installer {
+        Some(i) => i.clone(),
+        None => if quilt {
+            quilt_client
+                .get_latest_installer()
+                .await?
+                .map(|i| i.version)
+        } else {
+            fabric.get_latest_installer().await?.map(|i| i.version)
+        }
+        .ok_or(format!(
+            "No stable {} installer version found; pass --installer-version to choose one",
+            name
+        ))?,
+    };
 
     Ok(FabricVersion {
+        loader_type: loader_type.to_string(),
         loader: lv,
         game: gv,
         installer: iv,
@@ -132,31 +642,91 @@ async fn fetch_fabric_versions() -> Result<FabricVersion, Box<dyn std::error::Er
 /// Create mc.toml configuration file using McConfig helper
 async fn create_config_file(
     project_name: &str,
-    fabric_versions: &FabricVersion,
+    versions: &Versions,
+    memory: &str,
+    min_memory: Option<&String>,
+    jvm_flags: &[String],
+    java_path: Option<&String>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let mut config = McConfig::new(project_name.to_string());
-    config.versions = Versions {
-        mc_version: fabric_versions.game.clone(),
-        fabric_version: fabric_versions.loader.clone(),
-        mc_cli_version: String::from("0.1.0"),
-    };
+    config.versions = versions.clone();
+    let mut launch_cmd = vec![String::from("java"), format!("-Xmx{}", memory)];
+    if let Some(min) = min_memory {
+        launch_cmd.push(format!("-Xms{}", min));
+    }
+    launch_cmd.extend(jvm_flags.iter().cloned());
+    launch_cmd.extend([
+        String::from("-jar"),
+        String::from(server_jar(&versions.loader_type)),
+        String::from("nogui"),
+    ]);
     config.console = ConsoleConfig {
-        launch_cmd: vec![
-            String::from("java"),
-            String::from("-Xmx2G"),
-            String::from("-jar"),
-            String::from("server.jar"),
-            String::from("nogui"),
-        ],
+        launch_cmd,
+        java_path: java_path.cloned(),
     };
 
     config.save(PathBuf::from("mc.toml"))?;
-    println!("Created configuration file: mc.toml");
+    info!("Created configuration file: mc.toml");
+    Ok(())
+}
+
+/// Pick the Minecraft version (given, latest, or interactively chosen) and its
+/// latest stable Paper build; `None` if the user cancelled the selector
+pub async fn resolve_paper_version(
+    mc_version: Option<&String>,
+    headless: bool,
+    snapshots: bool,
+) -> Result<Option<(String, PaperBuild)>, Box<dyn std::error::Error>> {
+    let client = PaperClient::new()?;
+    let game = match mc_version {
+        Some(v) => v.clone(),
+        None if headless => client
+            .get_latest_version()
+            .await?
+            .ok_or("No stable Paper Minecraft version found; pass --mc-version to choose one")?,
+        None => {
+            let mut versions = client.get_versions().await?;
+            // Paper marks pre-releases with a suffix, e.g. `1.21-pre1`
+            if !snapshots {
+                versions.retain(|v| !v.contains('-'));
+            }
+            if versions.is_empty() {
+                return Err("No game versions available from the Paper API".into());
+            }
+            let Some(idx) = select_with_ratatui(game_version_title(snapshots), &versions)? else {
+                return Ok(None);
+            };
+            versions
+                .get(idx)
+                .cloned()
+                .ok_or("Selected game version is out of range")?
+        }
+    };
+    let build = client.get_latest_build(&game).await?.ok_or(format!(
+        "Paper has no stable build for Minecraft {} yet",
+        game
+    ))?;
+    Ok(Some((game, build)))
+}
+
+/// Download and verify the Paper server JAR for a build
+pub async fn download_paper_server_jar(
+    mc_version: &str,
+    build: &PaperBuild,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let url = PaperClient::new()?.download_url(mc_version, build);
+    let output_file = "server.jar";
+    info!("Downloading Paper server JAR from: {}", url);
+    let bytes = download_jar(&url, "Paper server").await?;
+    verify_sha256(&bytes, &build.downloads.application.sha256)
+        .map_err(|e| format!("Downloaded Paper JAR is corrupt. {}", e))?;
+    tokio::fs::write(output_file, &bytes).await?;
+    info!("Downloaded Paper server JAR to: {}", output_file);
     Ok(())
 }
 
 /// Download the Fabric server JAR for the selected versions
-async fn download_fabric_server_jar(
+pub async fn download_fabric_server_jar(
     fabric_versions: &FabricVersion,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let fabric_server_url = format!(
@@ -164,42 +734,151 @@ async fn download_fabric_server_jar(
         fabric_versions.game, fabric_versions.loader, fabric_versions.installer
     );
     let output_file = "server.jar".to_string();
-    println!("Downloading Fabric server JAR from: {}", fabric_server_url);
-    let response = reqwest::get(&fabric_server_url).await?;
-    let bytes = response.bytes().await?;
+    info!("Downloading Fabric server JAR from: {}", fabric_server_url);
+    let bytes = download_jar(&fabric_server_url, "Fabric server").await?;
     tokio::fs::write(&output_file, &bytes).await?;
-    println!("Downloaded Fabric server JAR to: {}", output_file);
+    info!("Downloaded Fabric server JAR to: {}", output_file);
+    Ok(())
+}
+
+/// Download a JAR, failing on an HTTP error or a body that is not a zip archive
+async fn download_jar(url: &str, label: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let bytes = download::fetch(url, label)
+        .await
+        .map_err(|e| format!("{}; check the selected versions", e))?;
+    if !download::is_zip(&bytes) {
+        return Err(format!("Response from {} is not a JAR file", url).into());
+    }
+    Ok(bytes)
+}
+
+/// Install a Quilt server by running the Quilt installer, which downloads the
+/// vanilla server and writes the quilt-server-launch.jar wrapper
+pub async fn install_quilt_server(
+    fabric_versions: &FabricVersion,
+    java: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let client = QuiltClient::new()?;
+    let installer = client
+        .get_installer_versions()
+        .await?
+        .into_iter()
+        .find(|i| i.version == fabric_versions.installer)
+        .ok_or(format!(
+            "Quilt installer version '{}' not found",
+            fabric_versions.installer
+        ))?;
+
+    let installer_file = "quilt-installer.jar";
+    info!("Downloading Quilt installer from: {}", installer.url);
+    let bytes = download_jar(&installer.url, "Quilt installer").await?;
+    tokio::fs::write(installer_file, &bytes).await?;
+
+    info!("Installing Quilt server...");
+    let status = SysCommand::new(java)
+        .args([
+            "-jar",
+            installer_file,
+            "install",
+            "server",
+            &fabric_versions.game,
+            &fabric_versions.loader,
+            "--download-server",
+            "--install-dir=.",
+        ])
+        .status()?;
+    let _ = tokio::fs::remove_file(installer_file).await;
+    if !status.success() {
+        return Err(format!("Quilt installer exited with status: {}", status).into());
+    }
+
+    info!("Installed Quilt server to: {}", server_jar("quilt"));
     Ok(())
 }
 
 // Start server once JAR is downloaded, to generate server files
-async fn initial_start_server() -> Result<(), Box<dyn std::error::Error>> {
-    let mut child = run_cmd(&["java", "-jar", "server.jar", "nogui"]).await?;
+async fn initial_start_server(java: &str, jar: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut child = SysCommand::new(java)
+        .args(["-jar", jar, "nogui"])
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    info!("Command started successfully with PID: {}", child.id());
+
+    // Echo stderr while keeping its tail for the error message
+    let stderr_tail = Arc::new(Mutex::new(VecDeque::new()));
+    if let Some(stderr) = child.stderr.take() {
+        let tail = Arc::clone(&stderr_tail);
+        std::thread::spawn(move || {
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                eprintln!("{}", line);
+                let mut tail = tail.lock().unwrap();
+                if tail.len() == STDERR_TAIL_LINES {
+                    tail.pop_front();
+                }
+                tail.push_back(line);
+            }
+        });
+    }
+    let stderr_text = || {
+        let tail = stderr_tail.lock().unwrap();
+        if tail.is_empty() {
+            String::new()
+        } else {
+            format!("\n{}", Vec::from(tail.clone()).join("\n"))
+        }
+    };
 
     // wait until both eula.txt and server.properties are created
     let eula_file = PathBuf::from("eula.txt");
     let props_file = PathBuf::from("server.properties");
+    let started = Instant::now();
     loop {
         let eula_exists = eula_file.exists();
         let props_exists = props_file.exists();
         if eula_exists && props_exists {
             break;
         }
-        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        if let Some(status) = child.try_wait()? {
+            // Give the reader thread a moment to drain the last lines
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            return Err(format!(
+                "Server exited ({}) before generating eula.txt and server.properties{}",
+                status,
+                stderr_text()
+            )
+            .into());
+        }
+        if started.elapsed() >= INITIAL_START_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(format!(
+                "Server did not generate eula.txt and server.properties within {}s{}",
+                INITIAL_START_TIMEOUT.as_secs(),
+                stderr_text()
+            )
+            .into());
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
     }
 
     // terminate process gracefully
     let _ = child.kill();
+    let _ = child.wait();
 
     Ok(())
 }
 
 /// Render a selectable table and prompt user for a choice, returning selected index
 #[allow(unused_assignments)]
-fn select_with_ratatui(title: &str, items: &[String]) -> Result<usize, Box<dyn std::error::Error>> {
-    // Setup terminal
+pub fn select_with_ratatui(
+    title: &str,
+    items: &[String],
+) -> Result<Option<usize>, Box<dyn std::error::Error>> {
+    // Setup terminal; the guard restores it on every exit path, including panics
     let mut stdout = io::stdout();
     terminal::enable_raw_mode()?;
+    let _guard = TerminalGuard;
     execute!(stdout, terminal::EnterAlternateScreen)?;
     let backend = ratatui::backend::CrosstermBackend::new(stdout);
     let mut terminal = ratatui::Terminal::new(backend)?;
@@ -209,7 +888,7 @@ fn select_with_ratatui(title: &str, items: &[String]) -> Result<usize, Box<dyn s
     let mut filtered_indices: Vec<usize> = (0..items.len()).collect();
     let mut selected: usize = 0; // index in filtered list
     let mut scroll: usize = 0; // top row in filtered list
-    let mut result: usize = 0; // final selected original index
+    let mut result: Option<usize> = None; // final selected original index; None if cancelled
 
     loop {
         terminal.draw(|f| {
@@ -221,7 +900,7 @@ fn select_with_ratatui(title: &str, items: &[String]) -> Result<usize, Box<dyn s
 
             // Search bar
             let search_text = format!(
-                "Search: {}  ({}/{})  ↑/↓ move • PgUp/PgDn scroll • Enter select",
+                "Search: {}  ({}/{})  ↑/↓ move • PgUp/PgDn scroll • Enter select • Esc cancel",
                 query,
                 filtered_indices.len(),
                 items.len()
@@ -276,10 +955,8 @@ fn select_with_ratatui(title: &str, items: &[String]) -> Result<usize, Box<dyn s
                 KeyCode::Up => {
                     selected = selected.saturating_sub(1);
                 }
-                KeyCode::Down => {
-                    if selected + 1 < filtered_indices.len() {
-                        selected += 1;
-                    }
+                KeyCode::Down if selected + 1 < filtered_indices.len() => {
+                    selected += 1;
                 }
                 KeyCode::PageUp => {
                     let visible = terminal.size()?.height as usize;
@@ -295,23 +972,17 @@ fn select_with_ratatui(title: &str, items: &[String]) -> Result<usize, Box<dyn s
                 KeyCode::Home => {
                     selected = 0;
                 }
-                KeyCode::End => {
-                    if !filtered_indices.is_empty() {
-                        selected = filtered_indices.len() - 1;
-                    }
+                KeyCode::End if !filtered_indices.is_empty() => {
+                    selected = filtered_indices.len() - 1;
                 }
-                KeyCode::Enter => {
-                    if filtered_indices.is_empty() {
-                        result = 0;
-                    } else {
-                        result = filtered_indices[selected];
-                    }
+                // Nothing to pick while the search matches no items
+                KeyCode::Enter if !filtered_indices.is_empty() => {
+                    result = Some(filtered_indices[selected]);
                     break;
                 }
                 KeyCode::Esc => {
                     if query.is_empty() {
-                        // Cancel selection: default to first
-                        result = 0;
+                        // Cancel selection
                         break;
                     } else {
                         // Clear search
@@ -322,23 +993,20 @@ fn select_with_ratatui(title: &str, items: &[String]) -> Result<usize, Box<dyn s
                     }
                 }
                 KeyCode::Char('q') => {
-                    result = 0;
                     break;
                 }
-                KeyCode::Backspace => {
-                    if !query.is_empty() {
-                        query.pop();
-                        // Refilter
-                        let qlower = query.to_lowercase();
-                        filtered_indices = items
-                            .iter()
-                            .enumerate()
-                            .filter(|(_, s)| s.to_lowercase().contains(&qlower))
-                            .map(|(i, _)| i)
-                            .collect();
-                        selected = 0;
-                        scroll = 0;
-                    }
+                KeyCode::Backspace if !query.is_empty() => {
+                    query.pop();
+                    // Refilter
+                    let qlower = query.to_lowercase();
+                    filtered_indices = items
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, s)| s.to_lowercase().contains(&qlower))
+                        .map(|(i, _)| i)
+                        .collect();
+                    selected = 0;
+                    scroll = 0;
                 }
                 KeyCode::Char(c) => {
                     // Update search query
@@ -358,16 +1026,47 @@ fn select_with_ratatui(title: &str, items: &[String]) -> Result<usize, Box<dyn s
         }
     }
 
-    // Restore terminal
-    terminal::disable_raw_mode()?;
-    let mut out = io::stdout();
-    execute!(out, terminal::LeaveAlternateScreen)?;
-
     Ok(result)
 }
 
-/// Initial setup of the server
-async fn initial_server_setup() -> Result<(), Box<dyn std::error::Error>> {
+/// Leaves raw mode and the alternate screen when dropped
+struct TerminalGuard;
+
+impl Drop for TerminalGuard {
+    fn drop(&mut self) {
+        // Errors are ignored: this also runs while unwinding, where there is no one to report to
+        let _ = terminal::disable_raw_mode();
+        let _ = execute!(
+            io::stdout(),
+            terminal::LeaveAlternateScreen,
+            crossterm::cursor::Show
+        );
+    }
+}
+
+/// Read a `--props-template` file, rejecting values of the wrong type for well-known keys
+fn load_props_template(path: &Path) -> Result<ServerProperties, Box<dyn std::error::Error>> {
+    let template = ServerProperties::from_file(path)
+        .map_err(|e| format!("Cannot read props template {}: {}", path.display(), e))?;
+    for (key, value) in template.entries() {
+        match PropType::for_key(key) {
+            Some(t) => t
+                .validate(value)
+                .map_err(|e| format!("{}: invalid value for '{}': {}", path.display(), key, e))?,
+            None => warn!(
+                "{}: '{}' is not a known property; applying without validation",
+                path.display(),
+                key
+            ),
+        }
+    }
+    Ok(template)
+}
+
+/// Initial setup of the server; `template` entries override the defaults below
+async fn initial_server_setup(
+    template: Option<&ServerProperties>,
+) -> Result<(), Box<dyn std::error::Error>> {
     // Read existing server.properties
     let mut server_props = ServerProperties::from_file(PathBuf::from("server.properties"))?;
 
@@ -385,15 +1084,21 @@ async fn initial_server_setup() -> Result<(), Box<dyn std::error::Error>> {
     server_props.set("rcon.port", "25575".to_string());
     server_props.set("rcon.password", "changeme".to_string());
 
+    if let Some(template) = template {
+        for (key, value) in template.entries() {
+            server_props.set(key, value);
+        }
+    }
+
     server_props.save(PathBuf::from("server.properties"))?;
-    println!("Created server properties file: server.properties");
+    info!("Created server properties file: server.properties");
 
-    // set eula to true, in eula.txt
+    // set eula to true, in eula.txt (accepted up front by confirm_eula)
     let mut eula_props = ServerProperties::from_file(PathBuf::from("eula.txt"))?;
     eula_props.set("eula", "true".to_string());
     eula_props.save(PathBuf::from("eula.txt"))?;
 
-    println!("Created eula.txt file: eula.txt");
+    info!("Created eula.txt file: eula.txt");
 
     Ok(())
 }
@@ -0,0 +1,159 @@
+use clap::{Arg, Command};
+use std::fs::{self, File};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tracing::info;
+
+/// Build the logs subcommand definition
+pub fn command() -> Command {
+    Command::new("logs")
+        .about("Print or follow the server log")
+        .arg(
+            Arg::new("lines")
+                .long("lines")
+                .short('n')
+                .value_name("N")
+                .help("Number of trailing lines to print")
+                .value_parser(clap::value_parser!(usize))
+                .default_value("50"),
+        )
+        .arg(
+            Arg::new("follow")
+                .long("follow")
+                .short('f')
+                .help("Keep printing new lines as they are appended")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("file")
+                .long("file")
+                .value_name("PATH")
+                .help("Log file to read")
+                .default_value("logs/latest.log"),
+        )
+}
+
+/// Execute the logs subcommand
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let lines = *matches.get_one::<usize>("lines").unwrap();
+    let path = PathBuf::from(matches.get_one::<String>("file").unwrap());
+
+    if !path.exists() {
+        return Err(format!("Log file not found: {}", path.display()).into());
+    }
+
+    let contents = fs::read(&path)?;
+    let text = String::from_utf8_lossy(&contents);
+    let all: Vec<&str> = text.lines().collect();
+    for line in &all[all.len().saturating_sub(lines)..] {
+        println!("{}", line);
+    }
+
+    if matches.get_flag("follow") {
+        follow(&path, Tail::at(&path, contents.len() as u64)).await?;
+    }
+
+    Ok(())
+}
+
+/// Poll the file for appended bytes until interrupted
+async fn follow(path: &Path, mut tail: Tail) -> Result<(), Box<dyn std::error::Error>> {
+    loop {
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let buf = tail.poll(path)?;
+        if !buf.is_empty() {
+            print!("{}", String::from_utf8_lossy(&buf));
+        }
+    }
+}
+
+/// Identity of the file behind a path: device and inode on Unix, creation time elsewhere
+#[cfg(unix)]
+type FileId = (u64, u64);
+#[cfg(not(unix))]
+type FileId = std::time::SystemTime;
+
+#[cfg(unix)]
+fn file_id(meta: &fs::Metadata) -> Option<FileId> {
+    use std::os::unix::fs::MetadataExt;
+    Some((meta.dev(), meta.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(meta: &fs::Metadata) -> Option<FileId> {
+    meta.created().ok()
+}
+
+/// Read position in a followed log and the file it belongs to
+struct Tail {
+    pos: u64,
+    id: Option<FileId>,
+}
+
+impl Tail {
+    /// Start following the file currently at `path` from byte `pos`
+    fn at(path: &Path, pos: u64) -> Self {
+        let id = fs::metadata(path).ok().as_ref().and_then(file_id);
+        Self { pos, id }
+    }
+
+    /// Read whatever was appended since the last poll, starting over when the
+    /// file was replaced (log rotation) or truncated in place
+    fn poll(&mut self, path: &Path) -> io::Result<Vec<u8>> {
+        // Reopen on every poll so a rotated file is picked up by path
+        let mut file = match File::open(path) {
+            Ok(f) => f,
+            Err(_) => return Ok(Vec::new()),
+        };
+        let meta = file.metadata()?;
+        let id = file_id(&meta);
+        if id != self.id {
+            info!("--- {} rotated, reading from start ---", path.display());
+            self.id = id;
+            self.pos = 0;
+        } else if meta.len() < self.pos {
+            info!("--- {} truncated, reading from start ---", path.display());
+            self.pos = 0;
+        }
+        if meta.len() == self.pos {
+            return Ok(Vec::new());
+        }
+
+        file.seek(SeekFrom::Start(self.pos))?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+        self.pos += buf.len() as u64;
+        Ok(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tail_restarts_after_rotation_and_truncation() {
+        let dir = std::env::temp_dir().join(format!("mc-cli_logs_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("latest.log");
+        fs::write(&path, "first\n").unwrap();
+
+        let mut tail = Tail::at(&path, 6);
+        assert!(tail.poll(&path).unwrap().is_empty());
+
+        fs::write(&path, "first\nsecond\n").unwrap();
+        assert_eq!(tail.poll(&path).unwrap(), b"second\n");
+
+        // Rotated to a new file that is already longer than the old read position
+        fs::rename(&path, dir.join("old.log")).unwrap();
+        fs::write(&path, "rotated log line\n").unwrap();
+        assert_eq!(tail.poll(&path).unwrap(), b"rotated log line\n");
+
+        // Truncated in place
+        fs::write(&path, "new\n").unwrap();
+        assert_eq!(tail.poll(&path).unwrap(), b"new\n");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
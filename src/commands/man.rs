@@ -0,0 +1,57 @@
+use clap::{Arg, Command};
+use clap_mangen::Man;
+use std::fs;
+use std::path::Path;
+use tracing::info;
+
+/// Build the man subcommand definition
+pub fn command() -> Command {
+    Command::new("man")
+        .about("Write roff man pages for every command to a directory")
+        .hide(true)
+        .arg(
+            Arg::new("out")
+                .long("out")
+                .short('o')
+                .value_name("DIR")
+                .help("Directory to write the pages into (created if missing)")
+                .required(true),
+        )
+}
+
+/// Execute the man subcommand
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let out = Path::new(matches.get_one::<String>("out").unwrap());
+    fs::create_dir_all(out)?;
+
+    // Building propagates global args and generated help/version flags into every subcommand
+    let mut cli = crate::cli().name(env!("CARGO_PKG_NAME"));
+    cli.build();
+
+    let mut written = 0;
+    render(&cli, out, &mut written)?;
+    info!("Wrote {} man page(s) to {}", written, out.display());
+    Ok(())
+}
+
+/// Write `<name>.1` for `cmd`, then recurse as `<name>-<sub>.1` for visible subcommands
+fn render(
+    cmd: &Command,
+    out: &Path,
+    written: &mut usize,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let name = cmd.get_name().to_string();
+    let mut page = Vec::new();
+    Man::new(cmd.clone()).render(&mut page)?;
+    fs::write(out.join(format!("{}.1", name)), page)?;
+    *written += 1;
+
+    for sub in cmd.get_subcommands() {
+        if sub.is_hide_set() || sub.get_name() == "help" {
+            continue;
+        }
+        let sub = sub.clone().name(format!("{}-{}", name, sub.get_name()));
+        render(&sub, out, written)?;
+    }
+    Ok(())
+}
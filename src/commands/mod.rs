@@ -1,10 +1,23 @@
+pub mod backup;
+pub mod completions;
+pub mod config;
 pub mod console;
+pub mod doctor;
 pub mod init;
+pub mod logs;
+pub mod man;
+pub mod moderation;
 pub mod mods;
+pub mod op;
 pub mod props;
+pub mod query;
+pub mod restore;
 pub mod run;
+pub mod say;
+pub mod server;
 pub mod status;
 pub mod stop;
+pub mod whitelist;
 
 // Central dispatcher mirroring mods/mod.rs style
 pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
@@ -16,6 +29,22 @@ pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::erro
         Some(("status", sub_matches)) => status::execute(sub_matches).await?,
         Some(("stop", sub_matches)) => stop::execute(sub_matches).await?,
         Some(("mods", sub_matches)) => mods::execute(sub_matches).await?,
+        Some(("logs", sub_matches)) => logs::execute(sub_matches).await?,
+        Some(("config", sub_matches)) => config::execute(sub_matches).await?,
+        Some(("query", sub_matches)) => query::execute(sub_matches).await?,
+        Some(("backup", sub_matches)) => backup::execute(sub_matches).await?,
+        Some(("restore", sub_matches)) => restore::execute(sub_matches).await?,
+        Some(("server", sub_matches)) => server::execute(sub_matches).await?,
+        Some(("doctor", sub_matches)) => doctor::execute(sub_matches).await?,
+        Some(("completions", sub_matches)) => completions::execute(sub_matches).await?,
+        Some(("man", sub_matches)) => man::execute(sub_matches).await?,
+        Some(("whitelist", sub_matches)) => whitelist::execute(sub_matches).await?,
+        Some(("op", sub_matches)) => op::execute(sub_matches).await?,
+        Some(("deop", sub_matches)) => op::execute_deop(sub_matches).await?,
+        Some(("say", sub_matches)) => say::execute(sub_matches).await?,
+        Some((action @ ("kick" | "ban" | "ban-ip" | "pardon" | "pardon-ip"), sub_matches)) => {
+            moderation::execute(action, sub_matches).await?
+        }
         _ => {
             println!("Unknown command. Use --help for more information.");
         }
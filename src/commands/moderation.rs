@@ -0,0 +1,116 @@
+use crate::commands::console::render_reply;
+use crate::utils::rcon::send_command;
+use clap::{Arg, Command};
+use std::net::IpAddr;
+
+/// Build the kick, ban, ban-ip, pardon and pardon-ip subcommand definitions
+pub fn commands() -> Vec<Command> {
+    let player = || {
+        Arg::new("target")
+            .value_name("PLAYER")
+            .help("Player name")
+            .required(true)
+            .value_parser(parse_player)
+            .index(1)
+    };
+    let ip = || {
+        Arg::new("target")
+            .value_name("IP")
+            .help("IPv4 or IPv6 address")
+            .required(true)
+            .value_parser(parse_ip)
+            .index(1)
+    };
+    let reason = || {
+        Arg::new("reason")
+            .help("Reason shown to the player; quote it to keep spaces")
+            .index(2)
+    };
+    vec![
+        Command::new("kick")
+            .about("Disconnect a player via RCON")
+            .arg(player())
+            .arg(reason()),
+        Command::new("ban")
+            .about("Ban a player via RCON")
+            .arg(player())
+            .arg(reason()),
+        Command::new("ban-ip")
+            .about("Ban an IP address via RCON")
+            .arg(ip())
+            .arg(reason()),
+        Command::new("pardon")
+            .about("Lift a player's ban via RCON")
+            .arg(player()),
+        Command::new("pardon-ip")
+            .about("Lift an IP ban via RCON")
+            .arg(ip()),
+    ]
+}
+
+/// Reject names the server could never match, such as blanks or names with spaces
+fn parse_player(value: &str) -> Result<String, String> {
+    if value.is_empty() || value.chars().any(char::is_whitespace) {
+        Err(format!("'{}' is not a valid player name", value))
+    } else {
+        Ok(value.to_string())
+    }
+}
+
+fn parse_ip(value: &str) -> Result<String, String> {
+    value
+        .parse::<IpAddr>()
+        .map(|ip| ip.to_string())
+        .map_err(|_| format!("'{}' is not a valid IP address", value))
+}
+
+/// Execute one of the moderation subcommands, named by `action`
+pub async fn execute(
+    action: &str,
+    matches: &clap::ArgMatches,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let rcon_cmd = rcon_command(
+        action,
+        matches.get_one::<String>("target").unwrap(),
+        matches.try_get_one::<String>("reason").ok().flatten(),
+    );
+    let reply = send_command(&rcon_cmd)
+        .await
+        .map_err(|e| format!("Failed to run '{}' via RCON: {}", rcon_cmd, e))?;
+    println!("{}", render_reply(&reply, false));
+    Ok(())
+}
+
+fn rcon_command(action: &str, target: &str, reason: Option<&String>) -> String {
+    match reason.map(|r| r.trim()).filter(|r| !r.is_empty()) {
+        Some(reason) => format!("{} {} {}", action, target, reason),
+        None => format!("{} {}", action, target),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rcon_command() {
+        let reason = "griefing spawn".to_string();
+        assert_eq!(
+            rcon_command("ban", "Steve", Some(&reason)),
+            "ban Steve griefing spawn"
+        );
+        assert_eq!(
+            rcon_command("pardon-ip", "10.0.0.1", None),
+            "pardon-ip 10.0.0.1"
+        );
+    }
+
+    #[test]
+    fn test_validates_targets() {
+        assert!(parse_player("Steve_1").is_ok());
+        assert!(parse_player("").is_err());
+        assert!(parse_player("two words").is_err());
+        assert_eq!(parse_ip("::1"), Ok("::1".to_string()));
+        assert!(parse_ip("Steve").is_err());
+    }
+}
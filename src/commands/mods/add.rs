@@ -1,12 +1,17 @@
-use crate::libs::modrinth::ModrinthClient;
-use crate::utils::config_file::McConfig;
+use crate::libs::modrinth::{ModrinthClient, Version, VersionFile, find_version};
+use crate::utils::config_file::{LOADER_TYPES, McConfig, ProjectKind};
+use crate::utils::mod_lock::{LockedMod, ModLock};
+use crate::utils::{checksum, download};
 use clap::{Arg, Command};
+use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::io::{self, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
 
 pub fn command() -> Command {
     Command::new("add")
-        .about("Add a mod entry to mc.toml [mods]")
+        .about("Add a mod, datapack or resourcepack entry to mc.toml")
         .arg(
             Arg::new("name")
                 .help("Mod slug/name to add")
@@ -15,140 +20,732 @@ pub fn command() -> Command {
         )
         .arg(
             Arg::new("version")
-                .help("Optional version string; if omitted, latest is used")
+                .help("Optional version number or Modrinth version id; if omitted, latest is used")
                 .required(false)
                 .index(2),
         )
+        .arg(
+            Arg::new("version_id")
+                .long("version-id")
+                .value_name("ID")
+                .help("Install the version with this Modrinth version id")
+                .conflicts_with("version"),
+        )
+        .arg(
+            Arg::new("type")
+                .long("type")
+                .short('t')
+                .value_name("TYPE")
+                .help("Kind of project to install")
+                .value_parser(["mod", "datapack", "resourcepack"])
+                .default_value("mod"),
+        )
+        .arg(
+            Arg::new("loader")
+                .long("loader")
+                .value_name("LOADER")
+                .help("Match versions against this loader instead of the one in mc.toml")
+                .value_parser(LOADER_TYPES.to_vec()),
+        )
+        .arg(
+            Arg::new("no_deps")
+                .long("no-deps")
+                .help("Do not install required dependencies")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(super::dir_arg())
+        .arg(
+            Arg::new("allow_client")
+                .long("allow-client")
+                .help("Install mods Modrinth marks as unsupported on servers (client-only)")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("dry_run")
+                .long("dry-run")
+                .help("Show what would be downloaded and changed without touching any files")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("force")
+                .long("force")
+                .help(
+                    "Overwrite existing files that differ from the resolved version without asking",
+                )
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+/// How `install` treats the requested project and its dependencies
+#[derive(Debug, Clone)]
+pub struct InstallOptions {
+    pub kind: ProjectKind,
+    /// Folder the files are downloaded into
+    pub dir: PathBuf,
+    /// Also install required dependencies
+    pub with_deps: bool,
+    /// Install mods with `server_side: unsupported` after a warning instead of refusing
+    pub allow_client: bool,
+    /// Match the requested version only against Modrinth version ids
+    pub version_is_id: bool,
+    /// Resolve and report the plan without downloading files or changing the config
+    pub dry_run: bool,
+    /// Overwrite files on disk that differ from the resolved version without asking
+    pub force: bool,
 }
 
 pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
     let slug = matches.get_one::<String>("name").unwrap().to_string();
-    let version_arg = matches.get_one::<String>("version").cloned();
-
-    // Ensure mods directory exists
-    let mods_dir = PathBuf::from("mods");
-    if !mods_dir.exists() {
-        fs::create_dir_all(&mods_dir)?;
-    }
+    let version_id = matches.get_one::<String>("version_id");
+    let version_arg = version_id.or(matches.get_one::<String>("version")).cloned();
+    let kind = ProjectKind::parse(matches.get_one::<String>("type").unwrap()).unwrap();
+    let dry_run = matches.get_flag("dry_run");
+    let options = InstallOptions {
+        kind,
+        dir: super::target_dir(matches, kind),
+        with_deps: !matches.get_flag("no_deps"),
+        allow_client: matches.get_flag("allow_client"),
+        version_is_id: version_id.is_some(),
+        dry_run,
+        force: matches.get_flag("force"),
+    };
 
     // Load config to know current MC/fabric versions for validation
     let mut config = McConfig::load()?;
-
-    // Resolve project details for compatibility checks
+    let mut lock = ModLock::load()?;
     let client = ModrinthClient::new()?;
-    let project = client.get_project(&slug).await?;
-    // Basic server-side compatibility check (values are often: "unsupported", "optional", "required")
-    if let Some(server_side) = project.server_side.as_deref()
-        && server_side == "unsupported"
-    {
+
+    // The override only applies to this lookup; mc.toml keeps its configured loader
+    let configured_loader = config.versions.loader_type.clone();
+    if let Some(loader) = matches.get_one::<String>("loader") {
+        config.versions.loader_type = loader.clone();
+    }
+
+    let result = install(
+        &client,
+        &mut config,
+        &mut lock,
+        &slug,
+        version_arg.as_deref(),
+        options,
+    )
+    .await;
+    config.versions.loader_type = configured_loader;
+    let pulled = result?;
+    if dry_run {
+        println!("Dry run: no files downloaded, mc.toml and mc.lock left unchanged.");
+        return Ok(());
+    }
+    config.save("mc.toml")?;
+    lock.save("mc.lock")?;
+
+    if !pulled.is_empty() {
+        info!("Installed {} dependency(ies) for {}:", pulled.len(), slug);
+        for (dep_slug, dep_version) in pulled.iter() {
+            info!("  {} {}", dep_slug, dep_version);
+        }
+    }
+    Ok(())
+}
+
+/// Install a project into the config and lockfile (without saving them), optionally
+/// pulling in its required dependencies. Returns the (slug, version) of each dependency installed.
+pub async fn install(
+    client: &ModrinthClient,
+    config: &mut McConfig,
+    lock: &mut ModLock,
+    slug: &str,
+    version_arg: Option<&str>,
+    options: InstallOptions,
+) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+    let kind = options.kind;
+    if kind == ProjectKind::Mod && !config.versions.is_modded() {
         return Err(format!(
-            "Project '{}' is not server-compatible (server_side=unsupported).",
-            slug
+            "This is a {} server, which does not load mods; only datapacks and resourcepacks can be added.",
+            config.versions.server_type
         )
         .into());
     }
+    // Key the entry by Modrinth's canonical slug, not the name as typed
+    let (slug, version) = resolve_version(client, config, slug, version_arg, &options).await?;
+    let slug = slug.as_str();
+    if let Some((key, installed)) = installed_entry(config, kind, slug) {
+        if key != slug {
+            if options.dry_run {
+                println!(
+                    "Would rename {} {} to {} in mc.toml",
+                    kind.as_str(),
+                    key,
+                    slug
+                );
+            } else {
+                rename_entry(config, lock, kind, &key, slug);
+                info!("Renamed {} entry '{}' to '{}'.", kind.as_str(), key, slug);
+            }
+        }
+        let same = installed == version.id
+            || version.version_number.as_deref() == Some(installed.as_str());
+        if version_arg.is_none() || same {
+            info!(
+                "{} is already installed at {}; use `mods update` to change it.",
+                slug, installed
+            );
+            return Ok(Vec::new());
+        }
+    }
 
-    // Resolve version via Modrinth if not provided
-    let (version_number, download_url, filename) = if let Some(vn) = version_arg.clone() {
-        // Find specific version by version_number
-        let versions = client.get_project_versions(&slug).await?;
-        let mut found = None;
-        for v in versions {
-            if v.version_number.as_deref() == Some(&vn) {
-                // Validate loaders and game version compatibility
-                // Ensure includes fabric loader if config is using fabric
-                if !v.loaders.is_empty() {
-                    let uses_fabric = !config.versions.fabric_version.is_empty();
-                    if uses_fabric && !v.loaders.iter().any(|l| l.eq_ignore_ascii_case("fabric")) {
-                        return Err(format!(
-                            "Version '{}' of '{}' does not declare Fabric loader support.",
-                            vn, slug
-                        )
-                        .into());
-                    }
-                }
-                // Validate game version match
-                if !v.game_versions.is_empty() {
-                    let mc_ver = &config.versions.mc_version;
-                    if !v.game_versions.iter().any(|gv| gv == mc_ver) {
-                        return Err(format!(
-                            "Version '{}' of '{}' targets game versions {:?}, not current '{}'.",
-                            vn, slug, v.game_versions, mc_ver
-                        )
-                        .into());
-                    }
-                }
-                // pick primary file or first
-                if let Some(file) = v
-                    .files
-                    .iter()
-                    .find(|f| f.primary.unwrap_or(false))
-                    .or_else(|| v.files.first())
-                {
-                    found = Some((vn.clone(), file.url.clone(), file.filename.clone()));
-                }
-                break;
+    // Resolve every artifact before downloading any, so a dependency that cannot
+    // be satisfied leaves no stray files behind
+    let mut seen: HashSet<String> = HashSet::from([version.project_id.clone()]);
+    let mut plan: Vec<(String, Version)> = vec![(slug.to_string(), version)];
+    let mut next = 0;
+    while options.with_deps && kind == ProjectKind::Mod && next < plan.len() {
+        // Walk required dependencies breadth-first to avoid async recursion
+        let deps: Vec<(Option<String>, Option<String>)> = plan[next]
+            .1
+            .dependencies
+            .iter()
+            .filter(|d| d.dependency_type == "required")
+            .map(|d| (d.version_id.clone(), d.project_id.clone()))
+            .collect();
+        next += 1;
+
+        for (version_id, project_id) in deps {
+            let pinned = match version_id.as_deref() {
+                Some(id) => Some(client.get_version(id).await?),
+                None => None,
+            };
+            let project_id = match (project_id, pinned.as_ref()) {
+                (Some(id), _) => id,
+                (None, Some(v)) => v.project_id.clone(),
+                (None, None) => continue,
+            };
+            if !seen.insert(project_id.clone()) {
+                continue;
+            }
+
+            let project = client.get_project(&project_id).await?;
+            if let Some(installed) = config.mods.installed.get(&project.slug)
+                && installed_satisfies(client, config, &project.slug, installed, pinned.as_ref())
+                    .await?
+            {
+                continue;
             }
+
+            let dep_version = match pinned {
+                Some(v) => v,
+                None => {
+                    resolve_version(client, config, &project.slug, None, &options)
+                        .await?
+                        .1
+                }
+            };
+            plan.push((project.slug.clone(), dep_version));
+        }
+    }
+
+    if options.dry_run {
+        for (plan_slug, plan_version) in plan.iter() {
+            print_planned(config, lock, kind, &options.dir, plan_slug, plan_version)?;
+        }
+        return Ok(plan
+            .iter()
+            .skip(1)
+            .map(|(s, v)| (s.clone(), version_label(v)))
+            .collect());
+    }
+
+    // Ask once, before downloading anything, about jars that would be replaced
+    let mut conflicts = Vec::new();
+    for (plan_slug, plan_version) in plan.iter() {
+        let file = primary_file(plan_version, plan_slug)?;
+        let path = options.dir.join(&file.filename);
+        if path.exists() && !file_matches(&path, file.hashes.sha512.as_deref()) {
+            conflicts.push(path);
         }
-        match found {
-            Some(tuple) => tuple,
-            None => {
-                return Err(format!("Version '{}' not found for project '{}'.", vn, slug).into());
+    }
+    if !conflicts.is_empty() && !confirm_overwrite(&conflicts, options.force)? {
+        return Err("Install cancelled; existing files were left in place.".into());
+    }
+
+    let mut pulled = Vec::new();
+    for (i, (plan_slug, plan_version)) in plan.iter().enumerate() {
+        let locked = download_version(plan_version, plan_slug, &options.dir).await?;
+
+        // Drop the jar of a previously installed version that this one replaces
+        if let Some(old) = lock
+            .get(plan_slug)
+            .filter(|o| kind == ProjectKind::Mod && o.filename != locked.filename)
+        {
+            let old_path = options.dir.join(&old.filename);
+            if old_path.exists() {
+                let _ = fs::remove_file(&old_path);
+                info!("Removed old jar: {}", old_path.display());
             }
         }
+
+        config
+            .installed_mut(kind)
+            .insert(plan_slug.clone(), locked.version_number.clone());
+        if i > 0 {
+            pulled.push((plan_slug.clone(), locked.version_number.clone()));
+        }
+        if kind == ProjectKind::Mod {
+            lock.upsert(locked);
+        }
+    }
+
+    Ok(pulled)
+}
+
+/// The installed entry (key, version) whose key matches `slug` ignoring case, so
+/// entries stored as typed before slugs were canonicalized are still found
+fn installed_entry(config: &McConfig, kind: ProjectKind, slug: &str) -> Option<(String, String)> {
+    let installed = config.installed(kind);
+    installed
+        .get_key_value(slug)
+        .or_else(|| installed.iter().find(|(k, _)| k.eq_ignore_ascii_case(slug)))
+        .map(|(k, v)| (k.clone(), v.clone()))
+}
+
+/// Move an mc.toml entry and its lock entry from `from` to the canonical slug `to`
+fn rename_entry(
+    config: &mut McConfig,
+    lock: &mut ModLock,
+    kind: ProjectKind,
+    from: &str,
+    to: &str,
+) {
+    let installed = config.installed_mut(kind);
+    if let Some(version) = installed.remove(from) {
+        installed.insert(to.to_string(), version);
+    }
+    if let Some(mut entry) = lock.get(from).cloned() {
+        lock.remove(from);
+        entry.slug = to.to_string();
+        lock.upsert(entry);
+    }
+}
+
+/// Print the file a planned install would download, the jar it would replace and
+/// the mc.toml entry it would write
+fn print_planned(
+    config: &McConfig,
+    lock: &ModLock,
+    kind: ProjectKind,
+    dir: &Path,
+    slug: &str,
+    version: &Version,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let file = primary_file(version, slug)?;
+    let label = version_label(version);
+    let path = dir.join(&file.filename);
+    if !path.exists() {
+        println!("Would download {} -> {}", file.url, path.display());
+    } else if file_matches(&path, file.hashes.sha512.as_deref()) {
+        println!("Would keep {} (already up to date)", path.display());
     } else {
-        // No explicit version: pick the latest compatible version (newest first)
-        let versions = client.get_project_versions(&slug).await?;
-        let uses_fabric = !config.versions.fabric_version.is_empty();
-        let mc_ver = &config.versions.mc_version;
+        println!(
+            "Would download {} and overwrite {}",
+            file.url,
+            path.display()
+        );
+    }
+    if let Some(old) = lock
+        .get(slug)
+        .filter(|o| kind == ProjectKind::Mod && o.filename != file.filename)
+    {
+        println!("Would delete {}", dir.join(&old.filename).display());
+    }
+    match config.installed(kind).get(slug) {
+        Some(current) if *current == label => {
+            println!(
+                "Would keep {} {} = \"{}\" in mc.toml",
+                kind.as_str(),
+                slug,
+                label
+            )
+        }
+        Some(current) => println!(
+            "Would change {} {} in mc.toml: \"{}\" -> \"{}\"",
+            kind.as_str(),
+            slug,
+            current,
+            label
+        ),
+        None => println!(
+            "Would add {} {} = \"{}\" to mc.toml",
+            kind.as_str(),
+            slug,
+            label
+        ),
+    }
+    Ok(())
+}
+
+/// Whether the installed version of a dependency is the pinned one or, when the
+/// dependency is not pinned, is still compatible with the configured game and loader
+async fn installed_satisfies(
+    client: &ModrinthClient,
+    config: &McConfig,
+    slug: &str,
+    installed: &str,
+    pinned: Option<&Version>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let is_installed =
+        |v: &Version| v.id == installed || v.version_number.as_deref() == Some(installed);
+    if let Some(v) = pinned {
+        return Ok(is_installed(v));
+    }
+    let versions = client.get_project_versions(slug).await?;
+    Ok(versions
+        .iter()
+        .any(|v| is_installed(v) && is_compatible(config, ProjectKind::Mod, v)))
+}
+
+/// Newest version (Modrinth lists newest first) usable with the configured game and loader
+pub fn latest_compatible<'a>(
+    config: &McConfig,
+    kind: ProjectKind,
+    versions: &'a [Version],
+) -> Option<&'a Version> {
+    versions.iter().find(|v| is_compatible(config, kind, v))
+}
+
+/// Whether a version is of the given kind and targets the configured game version and loader
+fn is_compatible(config: &McConfig, kind: ProjectKind, v: &Version) -> bool {
+    let mc_ver = &config.versions.mc_version;
+    let game_ok = v.game_versions.is_empty() || v.game_versions.iter().any(|gv| gv == mc_ver);
+    let uses_loader = kind == ProjectKind::Mod && !config.versions.fabric_version.is_empty();
+    let loader_ok = !uses_loader || config.versions.supports_loaders(&v.loaders);
+    kind.matches_loaders(&v.loaders) && game_ok && loader_ok
+}
+
+/// Validate the project and pick the requested version, or the latest compatible one.
+/// Returns the project's canonical slug alongside the version.
+async fn resolve_version(
+    client: &ModrinthClient,
+    config: &McConfig,
+    slug: &str,
+    version_arg: Option<&str>,
+    options: &InstallOptions,
+) -> Result<(String, Version), Box<dyn std::error::Error>> {
+    let kind = options.kind;
+    // Resolve project details for compatibility checks
+    let project = client.get_project(slug).await?;
+    if project.project_type != kind.project_type() {
+        return Err(format!(
+            "Project '{}' is a {}, not a {}.",
+            slug,
+            project.project_type,
+            kind.as_str()
+        )
+        .into());
+    }
+    // Basic server-side compatibility check (values are often: "unsupported", "optional", "required")
+    if kind == ProjectKind::Mod && project.server_side.as_deref() == Some("unsupported") {
+        let what = if project.client_side.as_deref() == Some("required") {
+            "a client-only mod"
+        } else {
+            "not server-compatible"
+        };
+        if !options.allow_client {
+            return Err(format!(
+                "Project '{}' is {} (server_side=unsupported). Pass --allow-client to install it anyway.",
+                slug, what
+            )
+            .into());
+        }
+        warn!(
+            "'{}' is {} (server_side=unsupported); installing anyway.",
+            slug, what
+        );
+    }
+
+    // Datapacks share the `mod` project type, so only keep versions of the requested kind
+    let versions: Vec<Version> = client
+        .get_project_versions(slug)
+        .await?
+        .into_iter()
+        .filter(|v| kind.matches_loaders(&v.loaders))
+        .collect();
+    if versions.is_empty() {
+        return Err(format!("Project '{}' has no {} versions.", slug, kind.as_str()).into());
+    }
+    let uses_loader = kind == ProjectKind::Mod && !config.versions.fabric_version.is_empty();
+    let loader_ok = |v: &Version| config.versions.supports_loaders(&v.loaders);
+    let mc_ver = &config.versions.mc_version;
 
+    if let Some(vn) = version_arg {
+        // Find specific version by id or version_number
+        let found = if options.version_is_id {
+            versions.iter().find(|v| v.id == vn)
+        } else {
+            find_version(&versions, vn)
+        };
+        let v = found
+            .cloned()
+            .ok_or_else(|| format!("Version '{}' not found for project '{}'.", vn, slug))?;
+
+        // Validate loaders and game version compatibility
+        // Ensure includes the configured loader if one is in use
+        if !v.loaders.is_empty() && uses_loader && !loader_ok(&v) {
+            return Err(format!(
+                "Version '{}' of '{}' does not declare {} loader support (supports: {}).",
+                vn,
+                slug,
+                config.versions.loader_type,
+                v.loaders.join(", ")
+            )
+            .into());
+        }
+        // Validate game version match
+        if !v.game_versions.is_empty() && !v.game_versions.iter().any(|gv| gv == mc_ver) {
+            return Err(format!(
+                "Version '{}' of '{}' targets game versions {:?}, not current '{}'.",
+                vn, slug, v.game_versions, mc_ver
+            )
+            .into());
+        }
+        Ok((project.slug, v))
+    } else {
+        // Point at the loaders the mod does support when none of its versions fit
+        if uses_loader && !versions.iter().any(loader_ok) {
+            let mut supported: Vec<&str> = versions
+                .iter()
+                .flat_map(|v| v.loaders.iter().map(|l| l.as_str()))
+                .collect();
+            supported.sort();
+            supported.dedup();
+            return Err(format!(
+                "'{}' does not support the {} loader; it supports: {}.",
+                slug,
+                config.versions.loader_type,
+                supported.join(", ")
+            )
+            .into());
+        }
+
+        // No explicit version: pick the latest compatible version (newest first)
         let v = versions
             .into_iter()
-            .find(|v| {
-                let loader_ok =
-                    !uses_fabric || v.loaders.iter().any(|l| l.eq_ignore_ascii_case("fabric"));
-                let game_ok =
-                    v.game_versions.is_empty() || v.game_versions.iter().any(|gv| gv == mc_ver);
-                loader_ok && game_ok
-            })
+            .find(|v| is_compatible(config, kind, v))
             .ok_or_else(|| {
                 format!(
                     "No compatible version of '{}' found for game '{}'{}.",
                     slug,
                     mc_ver,
-                    if uses_fabric {
-                        " with Fabric loader"
+                    if uses_loader {
+                        format!(" with {} loader", config.versions.loader_type)
                     } else {
-                        ""
+                        String::new()
                     }
                 )
             })?;
+        Ok((project.slug, v))
+    }
+}
 
-        let file = v
-            .files
-            .iter()
-            .find(|f| f.primary.unwrap_or(false))
-            .or_else(|| v.files.first())
-            .ok_or_else(|| format!("No files available for compatible version of '{}'.", slug))?;
-        (
-            v.version_number.clone().unwrap_or_else(|| v.id.clone()),
-            file.url.clone(),
-            file.filename.clone(),
+/// Download the primary file of a version into `target_dir`, returning the
+/// resolved artifact to record in mc.toml and mc.lock
+async fn download_version(
+    version: &Version,
+    slug: &str,
+    target_dir: &Path,
+) -> Result<LockedMod, Box<dyn std::error::Error>> {
+    let file = primary_file(version, slug)?;
+
+    // Ensure target directory exists
+    if !target_dir.exists() {
+        fs::create_dir_all(target_dir)?;
+    }
+
+    // Download file and verify it against the hash published by Modrinth
+    let target_path = target_dir.join(&file.filename);
+    if file_matches(&target_path, file.hashes.sha512.as_deref()) {
+        info!(
+            "{} is already up to date; skipping download.",
+            target_path.display()
+        );
+    } else {
+        download::fetch_to_file(&file.url, &target_path, file.hashes.sha512.as_deref(), None)
+            .await?;
+        info!("Downloaded: {} -> {}", file.filename, target_path.display());
+    }
+    Ok(LockedMod {
+        slug: slug.to_string(),
+        version_id: version.id.clone(),
+        version_number: version_label(version),
+        filename: file.filename.clone(),
+        url: file.url.clone(),
+        sha512: file.hashes.sha512.clone(),
+    })
+}
+
+/// Whether the file at `path` exists and matches the published SHA-512. Without a
+/// published hash the file cannot be trusted and is always re-downloaded.
+fn file_matches(path: &Path, sha512: Option<&str>) -> bool {
+    match (sha512, fs::read(path)) {
+        (Some(expected), Ok(bytes)) => checksum::verify_sha512(&bytes, expected).is_ok(),
+        _ => false,
+    }
+}
+
+/// Ask before replacing files that differ from the resolved version; without a
+/// terminal to ask on, --force is required instead
+fn confirm_overwrite(paths: &[PathBuf], force: bool) -> Result<bool, Box<dyn std::error::Error>> {
+    if force {
+        return Ok(true);
+    }
+    let list: Vec<String> = paths.iter().map(|p| p.display().to_string()).collect();
+    if !io::stdin().is_terminal() {
+        return Err(format!(
+            "{} already exist(s) with different contents; pass --force to overwrite",
+            list.join(", ")
         )
-    };
+        .into());
+    }
+    print!(
+        "{} already exist(s) with different contents. Overwrite? [y/N] ",
+        list.join(", ")
+    );
+    io::stdout().flush()?;
+    let mut input = String::new();
+    let read = io::stdin().read_line(&mut input)?;
+    Ok(read > 0 && matches!(input.trim().to_lowercase().as_str(), "y" | "yes"))
+}
 
-    // Download file
-    let target_path = mods_dir.join(&filename);
-    let bytes = reqwest::get(&download_url).await?.bytes().await?;
-    fs::write(&target_path, &bytes)?;
+/// The primary file of a version, or its first file when none is marked primary
+fn primary_file<'a>(
+    version: &'a Version,
+    slug: &str,
+) -> Result<&'a VersionFile, Box<dyn std::error::Error>> {
+    version
+        .files
+        .iter()
+        .find(|f| f.primary.unwrap_or(false))
+        .or_else(|| version.files.first())
+        .ok_or_else(|| format!("No files available for compatible version of '{}'.", slug).into())
+}
 
-    // Update mc.toml
-    config
-        .mods
-        .installed
-        .insert(slug.clone(), version_number.clone());
-    config.save("mc.toml")?;
+/// Version number recorded in mc.toml, falling back to the version id
+fn version_label(version: &Version) -> String {
+    version
+        .version_number
+        .clone()
+        .unwrap_or_else(|| version.id.clone())
+}
 
-    println!("Downloaded: {} -> {}", filename, target_path.display());
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn version(number: &str, game: &str, loader: &str) -> Version {
+        serde_json::from_value(serde_json::json!({
+            "id": number,
+            "project_id": "p",
+            "name": null,
+            "version_number": number,
+            "game_versions": [game],
+            "loaders": [loader],
+            "files": [],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_latest_compatible_skips_other_game_and_loader() {
+        let config = McConfig::from_str(
+            r#"
+name = "t"
+
+[versions]
+mc_version = "1.20.1"
+fabric_version = "0.15.0"
+mc_cli_version = "0.1.0"
+
+[mods]
+[datapacks]
+[resourcepacks]
+
+[console]
+launch_cmd = []
+"#,
+        )
+        .unwrap();
+        // Newest first, as returned by Modrinth
+        let versions = vec![
+            version("3.0.0", "1.21", "fabric"),
+            version("2.1.0", "1.20.1", "forge"),
+            version("2.0.0", "1.20.1", "fabric"),
+            version("1.0.0", "1.19.4", "fabric"),
+        ];
+        let latest = latest_compatible(&config, ProjectKind::Mod, &versions).unwrap();
+        assert_eq!(latest.version_number.as_deref(), Some("2.0.0"));
+
+        let none = vec![version("3.0.0", "1.21", "fabric")];
+        assert!(latest_compatible(&config, ProjectKind::Mod, &none).is_none());
+    }
+
+    #[test]
+    fn test_rename_entry_moves_typed_slug_to_canonical() {
+        let mut config = McConfig::from_str(
+            r#"
+name = "t"
+
+[versions]
+mc_version = "1.20.1"
+fabric_version = "0.15.0"
+mc_cli_version = "0.1.0"
+
+[mods]
+Sodium = "0.5.0"
+
+[datapacks]
+[resourcepacks]
+
+[console]
+launch_cmd = []
+"#,
+        )
+        .unwrap();
+        let mut lock = ModLock::default();
+        lock.upsert(LockedMod {
+            slug: "Sodium".to_string(),
+            version_id: "abc".to_string(),
+            version_number: "0.5.0".to_string(),
+            filename: "sodium-0.5.0.jar".to_string(),
+            url: "https://cdn.modrinth.com/sodium-0.5.0.jar".to_string(),
+            sha512: None,
+        });
+
+        let found = installed_entry(&config, ProjectKind::Mod, "sodium");
+        assert_eq!(found, Some(("Sodium".to_string(), "0.5.0".to_string())));
+        assert!(installed_entry(&config, ProjectKind::Mod, "lithium").is_none());
+
+        rename_entry(&mut config, &mut lock, ProjectKind::Mod, "Sodium", "sodium");
+        assert_eq!(config.mods.installed.len(), 1);
+        assert_eq!(
+            config.mods.installed.get("sodium").map(String::as_str),
+            Some("0.5.0")
+        );
+        assert!(lock.get("Sodium").is_none());
+        assert_eq!(lock.get("sodium").unwrap().filename, "sodium-0.5.0.jar");
+    }
+
+    #[test]
+    fn test_file_matches_checks_published_hash() {
+        let dir = std::env::temp_dir().join(format!("mc-cli_add_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let jar = dir.join("mod.jar");
+
+        assert!(!file_matches(&jar, Some(&checksum::sha512_hex(b"abc"))));
+        fs::write(&jar, b"abc").unwrap();
+        assert!(file_matches(&jar, Some(&checksum::sha512_hex(b"abc"))));
+        assert!(!file_matches(&jar, Some(&checksum::sha512_hex(b"abd"))));
+        assert!(!file_matches(&jar, None));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -0,0 +1,40 @@
+use crate::utils::config_file::McConfig;
+use clap::{Arg, Command};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use tracing::info;
+
+/// Shareable description of an installed mod set
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModpackManifest {
+    pub mc_version: String,
+    pub fabric_version: String,
+    pub mods: HashMap<String, String>,
+}
+
+pub fn command() -> Command {
+    Command::new("export")
+        .about("Export installed mods and versions to a JSON manifest")
+        .arg(
+            Arg::new("path")
+                .help("Path of the manifest to write")
+                .required(true)
+                .index(1),
+        )
+}
+
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let path = matches.get_one::<String>("path").unwrap();
+    let config = McConfig::load()?;
+
+    let manifest = ModpackManifest {
+        mc_version: config.versions.mc_version.clone(),
+        fabric_version: config.versions.fabric_version.clone(),
+        mods: config.mods.installed.clone(),
+    };
+    fs::write(path, serde_json::to_string_pretty(&manifest)?)?;
+
+    info!("Exported {} mod(s) to {}", manifest.mods.len(), path);
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use super::add;
+use super::export::ModpackManifest;
+use crate::libs::modrinth::ModrinthClient;
+use crate::utils::config_file::{McConfig, ProjectKind};
+use crate::utils::mod_lock::ModLock;
+use clap::{Arg, Command};
+use std::fs;
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+pub fn command() -> Command {
+    Command::new("import")
+        .about("Install every mod listed in a JSON manifest")
+        .arg(
+            Arg::new("path")
+                .help("Path of the manifest to read")
+                .required(true)
+                .index(1),
+        )
+}
+
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let path = matches.get_one::<String>("path").unwrap();
+    let manifest: ModpackManifest = serde_json::from_str(&fs::read_to_string(path)?)?;
+
+    let mut config = McConfig::load()?;
+    if manifest.mc_version != config.versions.mc_version {
+        warn!(
+            "manifest targets game '{}' but mc.toml uses '{}'. Incompatible mods will be skipped.",
+            manifest.mc_version, config.versions.mc_version
+        );
+    }
+    if manifest.fabric_version != config.versions.fabric_version {
+        warn!(
+            "manifest targets Fabric loader '{}' but mc.toml uses '{}'.",
+            manifest.fabric_version, config.versions.fabric_version
+        );
+    }
+
+    let mut lock = ModLock::load()?;
+    let client = ModrinthClient::new()?;
+    let mut installed = 0usize;
+    let mut failed: Vec<(String, String)> = Vec::new();
+    let mut entries: Vec<(&String, &String)> = manifest.mods.iter().collect();
+    entries.sort();
+    for (slug, version) in entries {
+        if config.mods.installed.get(slug) == Some(version) {
+            info!("Already installed: {} {}", slug, version);
+            continue;
+        }
+        // The manifest lists the full mod set, so dependencies are not resolved again
+        match add::install(
+            &client,
+            &mut config,
+            &mut lock,
+            slug,
+            Some(version),
+            add::InstallOptions {
+                kind: ProjectKind::Mod,
+                dir: PathBuf::from(ProjectKind::Mod.dir()),
+                with_deps: false,
+                allow_client: false,
+                version_is_id: false,
+                dry_run: false,
+                force: false,
+            },
+        )
+        .await
+        {
+            Ok(_) => installed += 1,
+            Err(e) => failed.push((slug.clone(), e.to_string())),
+        }
+    }
+
+    config.save("mc.toml")?;
+    lock.save("mc.lock")?;
+    info!("Imported {} mod(s).", installed);
+    if !failed.is_empty() {
+        warn!("Skipped {} incompatible mod(s):", failed.len());
+        for (slug, reason) in failed.iter() {
+            warn!("  {}: {}", slug, reason);
+        }
+        return Err(format!("{} mod(s) could not be imported", failed.len()).into());
+    }
+    Ok(())
+}
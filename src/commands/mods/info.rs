@@ -0,0 +1,111 @@
+use crate::libs::modrinth::{ModrinthClient, project_url};
+use crate::utils::browser;
+use crate::utils::config_file::McConfig;
+use clap::{Arg, Command};
+
+extern crate modern_terminal;
+use crate::utils::console_log::{field, header};
+use modern_terminal::{
+    components::table::{Size, Table},
+    core::console::Console,
+    core::render::Render,
+};
+
+const MAX_VERSIONS: usize = 5;
+
+pub fn command() -> Command {
+    Command::new("info")
+        .about("Show details of a Modrinth project")
+        .arg(
+            Arg::new("name")
+                .help("Project slug or id")
+                .required(true)
+                .index(1),
+        )
+        .arg(
+            Arg::new("open")
+                .long("open")
+                .help("Also open the project's Modrinth page in the default browser")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let slug = matches.get_one::<String>("name").unwrap().to_string();
+    let client = ModrinthClient::new()?;
+
+    let project = client
+        .find_project(&slug)
+        .await?
+        .ok_or_else(|| format!("Could not find project '{}' on Modrinth.", slug))?;
+
+    let or_dash = |v: Option<&String>| v.cloned().unwrap_or_else(|| String::from("-"));
+    let details = vec![
+        ("Title", project.title.clone()),
+        ("Slug", project.slug.clone()),
+        ("Type", project.project_type.clone()),
+        ("Description", project.description.clone()),
+        ("Downloads", project.downloads.to_string()),
+        ("Client side", or_dash(project.client_side.as_ref())),
+        ("Server side", or_dash(project.server_side.as_ref())),
+        ("Categories", project.categories.join(", ")),
+    ];
+
+    let mut rows: Vec<Vec<Box<dyn Render>>> = Vec::new();
+    for (name, value) in details {
+        rows.push(vec![header(name.to_string()), field(value)]);
+    }
+    let component: Table = Table {
+        column_sizes: vec![Size::Cells(20), Size::Cells(60)],
+        rows,
+    };
+
+    let mut writer = std::io::stdout();
+    let mut console = Console::from_fd(&mut writer);
+    console.render(&component)?;
+
+    if matches.get_flag("open") {
+        browser::open_or_print(&project_url(&project.slug));
+    }
+
+    // Show the newest versions, narrowed to the project's game version when in one
+    let mc_version = McConfig::load().ok().map(|c| c.versions.mc_version);
+    let versions = client.get_project_versions(&project.slug).await?;
+    let compatible: Vec<_> = versions
+        .iter()
+        .filter(|v| match mc_version.as_ref() {
+            Some(mc) => v.game_versions.is_empty() || v.game_versions.contains(mc),
+            None => true,
+        })
+        .take(MAX_VERSIONS)
+        .collect();
+
+    match mc_version.as_ref() {
+        Some(mc) => println!("Latest versions for {}:", mc),
+        None => println!("Latest versions:"),
+    }
+    if compatible.is_empty() {
+        println!("  none");
+        return Ok(());
+    }
+
+    let mut rows: Vec<Vec<Box<dyn Render>>> = vec![vec![
+        header("Version".to_string()),
+        header("Game Versions".to_string()),
+        header("Loaders".to_string()),
+    ]];
+    for v in compatible {
+        rows.push(vec![
+            field(v.version_number.clone().unwrap_or_else(|| v.id.clone())),
+            field(v.game_versions.join(", ")),
+            field(v.loaders.join(", ")),
+        ]);
+    }
+    let component: Table = Table {
+        column_sizes: vec![Size::Cells(20), Size::Cells(40), Size::Cells(20)],
+        rows,
+    };
+    console.render(&component)?;
+
+    Ok(())
+}
@@ -1,6 +1,9 @@
-use crate::libs::modrinth::ModrinthClient;
-use crate::utils::config_file::McConfig;
-use clap::Command;
+use crate::commands::mods::add::latest_compatible;
+use crate::libs::modrinth::{CONCURRENT_LOOKUPS, ModrinthClient};
+use crate::utils::config_file::{McConfig, ProjectKind};
+use crate::utils::network;
+use clap::{Arg, Command};
+use futures::stream::{self, StreamExt};
 
 extern crate modern_terminal;
 use crate::utils::console_log::{field, header};
@@ -10,12 +13,49 @@ use modern_terminal::{
 };
 
 pub fn command() -> Command {
-    Command::new("list").about("List installed mods and show latest available version")
+    Command::new("list")
+        .about("List installed mods and show latest available version")
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .help("Print installed and latest versions as JSON instead of a table")
+                .action(clap::ArgAction::SetTrue),
+        )
 }
 
-pub async fn execute(_matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
     let config = McConfig::load()?;
-    let client = ModrinthClient::new()?;
+
+    // Sort for stable output; `buffered` yields results in input order
+    let mut installed: Vec<(&String, &String)> = config.mods.installed.iter().collect();
+    installed.sort();
+    // Offline, list what mc.toml records and leave the latest column unknown
+    let latest_versions: Vec<String> = if network::is_offline() {
+        vec![String::from("-"); installed.len()]
+    } else {
+        let client = ModrinthClient::new()?;
+        stream::iter(installed.iter())
+            .map(|(slug, _)| latest_version(&client, &config, slug))
+            .buffered(CONCURRENT_LOOKUPS)
+            .collect()
+            .await
+    };
+
+    if matches.get_flag("json") {
+        let entries: Vec<serde_json::Value> = installed
+            .iter()
+            .zip(latest_versions.iter())
+            .map(|((slug, installed_version), latest)| {
+                serde_json::json!({
+                    "slug": slug,
+                    "installed": installed_version,
+                    "latest": (latest != "-").then_some(latest),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
 
     // Prepare table rows
     let mut rows: Vec<Vec<Box<dyn modern_terminal::core::render::Render>>> = Vec::new();
@@ -34,20 +74,7 @@ pub async fn execute(_matches: &clap::ArgMatches) -> Result<(), Box<dyn std::err
         },
     ]);
 
-    for (slug, installed_version) in config.mods.installed.iter() {
-        // Query Modrinth to find the latest version; use first entry
-        let versions = client.get_project_versions(slug).await;
-        let latest_version = match versions {
-            Ok(vs) => {
-                if let Some(v) = vs.into_iter().next() {
-                    v.version_number.clone().unwrap_or_else(|| v.id.clone())
-                } else {
-                    String::from("-")
-                }
-            }
-            Err(_) => String::from("-"),
-        };
-
+    for ((slug, installed_version), latest_version) in installed.into_iter().zip(latest_versions) {
         rows.push(vec![
             {
                 let b: Box<dyn modern_terminal::core::render::Render> = field(slug.clone());
@@ -76,3 +103,15 @@ pub async fn execute(_matches: &clap::ArgMatches) -> Result<(), Box<dyn std::err
 
     Ok(())
 }
+
+/// Query Modrinth for the latest version compatible with the configured game and
+/// loader; "-" if unknown
+async fn latest_version(client: &ModrinthClient, config: &McConfig, slug: &str) -> String {
+    match client.get_project_versions(slug).await {
+        Ok(vs) => match latest_compatible(config, ProjectKind::Mod, &vs) {
+            Some(v) => v.version_number.clone().unwrap_or_else(|| v.id.clone()),
+            None => String::from("-"),
+        },
+        Err(_) => String::from("-"),
+    }
+}
@@ -1,9 +1,17 @@
-use clap::Command;
+use crate::utils::config_file::ProjectKind;
+use clap::{Arg, Command};
+use std::path::PathBuf;
 
 pub mod add;
+pub mod export;
+pub mod import;
+pub mod info;
 pub mod list;
+pub mod outdated;
+pub mod prune;
 pub mod remove;
 pub mod search;
+pub mod sync;
 pub mod update;
 
 pub fn command() -> Command {
@@ -14,6 +22,28 @@ pub fn command() -> Command {
         .subcommand(remove::command())
         .subcommand(list::command())
         .subcommand(update::command())
+        .subcommand(outdated::command())
+        .subcommand(export::command())
+        .subcommand(import::command())
+        .subcommand(info::command())
+        .subcommand(sync::command())
+        .subcommand(prune::command())
+}
+
+/// `--dir` option of the commands that read or write project files
+fn dir_arg() -> Arg {
+    Arg::new("dir")
+        .long("dir")
+        .value_name("PATH")
+        .help("Folder to use instead of mods/ (or datapacks/, resourcepacks/ per --type)")
+}
+
+/// Folder the files of `kind` live in: `--dir` when given, else the kind's default
+fn target_dir(matches: &clap::ArgMatches, kind: ProjectKind) -> PathBuf {
+    matches
+        .get_one::<String>("dir")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(kind.dir()))
 }
 
 pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
@@ -23,6 +53,12 @@ pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::erro
         Some(("remove", sub_matches)) => remove::execute(sub_matches).await?,
         Some(("list", sub_matches)) => list::execute(sub_matches).await?,
         Some(("update", sub_matches)) => update::execute(sub_matches).await?,
+        Some(("outdated", sub_matches)) => outdated::execute(sub_matches).await?,
+        Some(("export", sub_matches)) => export::execute(sub_matches).await?,
+        Some(("import", sub_matches)) => import::execute(sub_matches).await?,
+        Some(("info", sub_matches)) => info::execute(sub_matches).await?,
+        Some(("sync", sub_matches)) => sync::execute(sub_matches).await?,
+        Some(("prune", sub_matches)) => prune::execute(sub_matches).await?,
         _ => {
             println!("Use a subcommand, e.g., 'mods search --help'.");
         }
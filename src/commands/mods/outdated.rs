@@ -0,0 +1,41 @@
+use crate::commands::mods::update::{
+    UpdateCandidate, UpdateStatus, collect_candidates, render_table,
+};
+use crate::libs::modrinth::ModrinthClient;
+use crate::utils::config_file::McConfig;
+use clap::Command;
+use tracing::info;
+
+pub fn command() -> Command {
+    Command::new("outdated").about(
+        "Show installed mods with newer versions available; exits non-zero if any are outdated",
+    )
+}
+
+pub async fn execute(_matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let config = McConfig::load()?;
+    let client = ModrinthClient::new()?;
+    let candidates = collect_candidates(&client, &config).await;
+
+    render_table(&candidates, outdated_status)?;
+    let outdated = candidates.iter().filter(|c| c.has_update()).count();
+    if outdated > 0 {
+        return Err(format!(
+            "{} mod(s) outdated; run `mods update` to update them",
+            outdated
+        )
+        .into());
+    }
+    info!("All mods are current.");
+    Ok(())
+}
+
+/// Status column of the report: current (including pinned versions newer than the
+/// latest compatible one), outdated, or unknown when the lookup failed
+fn outdated_status(c: &UpdateCandidate) -> &'static str {
+    match c.status() {
+        UpdateStatus::Available => "outdated",
+        UpdateStatus::Unknown => "unknown",
+        UpdateStatus::UpToDate | UpdateStatus::Pinned => "current",
+    }
+}
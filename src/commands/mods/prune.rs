@@ -0,0 +1,144 @@
+use super::remove::resolve_filename;
+use crate::libs::modrinth::{CONCURRENT_LOOKUPS, ModrinthClient};
+use crate::utils::config_file::{McConfig, ProjectKind};
+use crate::utils::mod_lock::ModLock;
+use clap::{Arg, Command};
+use futures::stream::{self, StreamExt};
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use tracing::info;
+
+pub fn command() -> Command {
+    Command::new("prune")
+        .about("Delete jars in mods/ that are not tracked in mc.toml")
+        .arg(super::dir_arg())
+        .arg(
+            Arg::new("dry_run")
+                .long("dry-run")
+                .help("Only report orphaned jars, do not delete them")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("yes")
+                .long("yes")
+                .short('y')
+                .help("Assume yes; delete without confirmation")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let mut dry_run = matches.get_flag("dry_run");
+    let assume_yes = matches.get_flag("yes");
+
+    let mods_dir = super::target_dir(matches, ProjectKind::Mod);
+    if !mods_dir.exists() {
+        info!("No mods directory found at {}.", mods_dir.display());
+        return Ok(());
+    }
+
+    let config = McConfig::load()?;
+    let lock = ModLock::load()?;
+
+    // Collect the filenames of every tracked mod, preferring those pinned in mc.lock
+    let mut tracked: HashSet<String> = HashSet::new();
+    let mut lookups: Vec<(String, String)> = Vec::new();
+    for (slug, version) in config.mods.installed.iter() {
+        match lock.get(slug) {
+            Some(entry) => {
+                tracked.insert(entry.filename.clone());
+            }
+            None => lookups.push((slug.clone(), version.clone())),
+        }
+    }
+    lookups.sort();
+
+    let client = ModrinthClient::new()?;
+    let resolved: Vec<(String, Option<String>)> = stream::iter(lookups)
+        .map(|(slug, version)| {
+            let client = &client;
+            async move {
+                let filename = resolve_filename(client, &slug, &version)
+                    .await
+                    .ok()
+                    .flatten();
+                (slug, filename)
+            }
+        })
+        .buffered(CONCURRENT_LOOKUPS)
+        .collect()
+        .await;
+
+    let mut unresolved: Vec<String> = Vec::new();
+    for (slug, filename) in resolved {
+        match filename {
+            Some(filename) => {
+                tracked.insert(filename);
+            }
+            None => unresolved.push(slug),
+        }
+    }
+
+    let mut orphans: Vec<PathBuf> = fs::read_dir(&mods_dir)?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        // `.part` files are downloads that were interrupted before being moved into place
+        .filter(|p| {
+            p.extension()
+                .is_some_and(|ext| ext == "jar" || ext == "part")
+        })
+        .filter(|p| {
+            p.file_name()
+                .map(|n| !tracked.contains(n.to_string_lossy().as_ref()))
+                .unwrap_or(false)
+        })
+        .collect();
+    orphans.sort();
+
+    if orphans.is_empty() {
+        info!("No orphaned jars found.");
+        return Ok(());
+    }
+
+    println!("Orphaned jars:");
+    for path in orphans.iter() {
+        println!("  {}", path.display());
+    }
+
+    // Without every tracked filename a listed jar may still be in use
+    if !unresolved.is_empty() {
+        println!(
+            "Could not resolve files for: {}. Not deleting anything.",
+            unresolved.join(", ")
+        );
+        dry_run = true;
+    }
+    if dry_run {
+        return Ok(());
+    }
+
+    let proceed = if assume_yes {
+        true
+    } else {
+        print!("Delete {} jar(s)? [y/N] ", orphans.len());
+        io::stdout().flush()?;
+        let mut input = String::new();
+        let read = io::stdin().read_line(&mut input)?;
+        if read == 0 {
+            false
+        } else {
+            matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
+        }
+    };
+    if !proceed {
+        info!("Prune cancelled.");
+        return Ok(());
+    }
+
+    for path in orphans.iter() {
+        fs::remove_file(path)?;
+        info!("Deleted: {}", path.display());
+    }
+    Ok(())
+}
@@ -1,71 +1,137 @@
-use crate::libs::modrinth::ModrinthClient;
-use crate::utils::config_file::McConfig;
+use crate::libs::modrinth::{ModrinthClient, find_version};
+use crate::utils::config_file::{McConfig, ProjectKind};
+use crate::utils::mod_lock::ModLock;
 use clap::{Arg, Command};
 use std::fs;
-use std::path::PathBuf;
+use tracing::{info, warn};
 
 pub fn command() -> Command {
     Command::new("remove")
-        .about("Remove a mod entry from mc.toml [mods]")
+        .about("Remove a mod, datapack or resourcepack entry from mc.toml")
         .arg(
             Arg::new("name")
                 .help("Mod slug/name to remove")
                 .required(true)
                 .index(1),
         )
+        .arg(
+            Arg::new("type")
+                .long("type")
+                .short('t')
+                .value_name("TYPE")
+                .help("Kind of project to remove")
+                .value_parser(["mod", "datapack", "resourcepack"])
+                .default_value("mod"),
+        )
+        .arg(super::dir_arg())
+        .arg(
+            Arg::new("keep_file")
+                .long("keep-file")
+                .help("Remove the config entry but leave the file on disk")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("dry_run")
+                .long("dry-run")
+                .help("Show which file and config entry would be removed without changing anything")
+                .action(clap::ArgAction::SetTrue),
+        )
 }
 
 pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
     let slug = matches.get_one::<String>("name").unwrap().to_string();
+    let kind = ProjectKind::parse(matches.get_one::<String>("type").unwrap()).unwrap();
+    let keep_file = matches.get_flag("keep_file");
+    let dry_run = matches.get_flag("dry_run");
+    let dir = super::target_dir(matches, kind);
 
     let mut config = McConfig::load()?;
+    let mut lock = ModLock::load()?;
 
-    // Determine installed version to locate jar file
-    if let Some(installed_version) = config.mods.installed.get(&slug).cloned() {
-        // Try to resolve file name from Modrinth for the installed version
-        let client = ModrinthClient::new()?;
-        let versions = client.get_project_versions(&slug).await?;
+    // Determine installed version to locate the file
+    let installed_version = match config.installed(kind).get(&slug).cloned() {
+        Some(v) => v,
+        None => return Err(format!("{} not found: {}", kind.as_str(), slug).into()),
+    };
 
-        let mut target_filename: Option<String> = None;
-        for v in versions {
-            if v.version_number.as_deref() == Some(installed_version.as_str())
-                || v.id == installed_version
-            {
-                if let Some(file) = v
-                    .files
-                    .iter()
-                    .find(|f| f.primary.unwrap_or(false))
-                    .or_else(|| v.files.first())
-                {
-                    target_filename = Some(file.filename.clone());
+    if !keep_file {
+        // Prefer the filename pinned in mc.lock, else resolve it from Modrinth
+        let target_filename = match lock.get(&slug).filter(|_| kind == ProjectKind::Mod) {
+            Some(entry) => Ok(Some(entry.filename.clone())),
+            None => match ModrinthClient::new() {
+                Ok(client) => resolve_filename(&client, &slug, &installed_version).await,
+                Err(e) => Err(e),
+            },
+        };
+
+        // Delete local file if we identified a filename
+        match target_filename {
+            Ok(Some(filename)) => {
+                let path = dir.join(&filename);
+                if dry_run {
+                    if path.exists() {
+                        println!("Would delete {}", path.display());
+                    } else {
+                        println!("Would skip {} (not found locally)", path.display());
+                    }
+                } else if path.exists() {
+                    let _ = fs::remove_file(&path);
+                    info!("Deleted local file: {}", path.display());
+                } else {
+                    info!("File not found locally: {}", path.display());
                 }
-                break;
             }
+            Ok(None) => info!(
+                "Could not resolve filename for installed version '{}' of '{}'.",
+                installed_version, slug
+            ),
+            Err(e) => warn!(
+                "could not look up '{}' on Modrinth ({}); the file in {} was not removed.",
+                slug,
+                e,
+                dir.display()
+            ),
         }
+    }
 
-        // Delete local jar if we identified a filename
-        if let Some(filename) = target_filename {
-            let path = PathBuf::from("mods").join(&filename);
-            if path.exists() {
-                let _ = fs::remove_file(&path);
-                println!("Deleted local jar: {}", path.display());
-            } else {
-                println!("Jar not found locally: {}", path.display());
-            }
-        } else {
-            println!(
-                "Could not resolve jar filename for installed version '{}' of '{}'.",
-                installed_version, slug
-            );
+    if dry_run {
+        println!(
+            "Would remove {} {} = \"{}\" from mc.toml",
+            kind.as_str(),
+            slug,
+            installed_version
+        );
+        if kind == ProjectKind::Mod && lock.get(&slug).is_some() {
+            println!("Would remove {} from mc.lock", slug);
         }
+        println!("Dry run: no files deleted, mc.toml and mc.lock left unchanged.");
+        return Ok(());
+    }
 
-        // Remove from config
-        config.mods.installed.remove(&slug);
-        config.save("mc.toml")?;
-        println!("Removed mod: {}", slug);
-    } else {
-        println!("Mod not found: {}", slug);
+    // Remove from config
+    config.installed_mut(kind).remove(&slug);
+    config.save("mc.toml")?;
+    if kind == ProjectKind::Mod && lock.remove(&slug) {
+        lock.save("mc.lock")?;
     }
+    info!("Removed {}: {}", kind.as_str(), slug);
 
     Ok(())
 }
+
+/// Find the primary filename of an installed version via Modrinth
+pub async fn resolve_filename(
+    client: &ModrinthClient,
+    slug: &str,
+    installed_version: &str,
+) -> crate::error::Result<Option<String>> {
+    let versions = client.get_project_versions(slug).await?;
+
+    Ok(find_version(&versions, installed_version).and_then(|v| {
+        v.files
+            .iter()
+            .find(|f| f.primary.unwrap_or(false))
+            .or_else(|| v.files.first())
+            .map(|f| f.filename.clone())
+    }))
+}
@@ -1,5 +1,6 @@
 use crate::{
-    libs::modrinth::{ModrinthClient, SearchQuery},
+    libs::modrinth::{ModrinthClient, ProjectResult, SearchQuery, project_url},
+    utils::browser,
     utils::console_log::{field, header},
 };
 use clap::{Arg, Command};
@@ -10,6 +11,32 @@ use modern_terminal::{
     core::console::Console,
 };
 
+/// Values Modrinth uses for a project's `client_side` and `server_side` support
+const SIDE_SUPPORT: &[&str] = &["required", "optional", "unsupported"];
+
+/// Modrinth's mod categories, excluding loaders (those go through `--loaders`)
+const CATEGORIES: &[&str] = &[
+    "adventure",
+    "cursed",
+    "decoration",
+    "economy",
+    "equipment",
+    "food",
+    "game-mechanics",
+    "library",
+    "magic",
+    "management",
+    "minigame",
+    "mobs",
+    "optimization",
+    "social",
+    "storage",
+    "technology",
+    "transportation",
+    "utility",
+    "worldgen",
+];
+
 pub fn command() -> Command {
     Command::new("search")
         .about("Search mods on Modrinth")
@@ -35,6 +62,71 @@ pub fn command() -> Command {
                 .num_args(1)
                 .required(false),
         )
+        .arg(
+            Arg::new("category")
+                .help("Only show mods in this category; repeat to require several")
+                .long("category")
+                .short('c')
+                .value_name("CAT")
+                .value_parser(CATEGORIES.to_vec())
+                .action(clap::ArgAction::Append),
+        )
+        .arg(
+            Arg::new("limit")
+                .help("Number of results per page (max 100)")
+                .long("limit")
+                .value_parser(clap::value_parser!(u32).range(1..=100))
+                .default_value("20"),
+        )
+        .arg(
+            Arg::new("offset")
+                .help("Number of results to skip")
+                .long("offset")
+                .value_parser(clap::value_parser!(u32))
+                .conflicts_with("page"),
+        )
+        .arg(
+            Arg::new("page")
+                .help("Page number to show, starting at 1")
+                .long("page")
+                .short('p')
+                .value_parser(clap::value_parser!(u32).range(1..)),
+        )
+        .arg(
+            Arg::new("sort")
+                .help("Sort order of results")
+                .long("sort")
+                .short('s')
+                .value_parser(["relevance", "downloads", "follows", "newest", "updated"])
+                .default_value("relevance"),
+        )
+        .arg(
+            Arg::new("server_only")
+                .long("server-only")
+                .help("Only show mods that run on a server (server_side required or optional)")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("server_side")
+                .long("server-side")
+                .value_name("SUPPORT")
+                .help("Only show mods with exactly this server_side support")
+                .value_parser(SIDE_SUPPORT.to_vec())
+                .conflicts_with("server_only"),
+        )
+        .arg(
+            Arg::new("open")
+                .long("open")
+                .value_name("SLUG|N")
+                .help("Open a project's Modrinth page: a result's # from this page, or a slug")
+                .conflicts_with("json"),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .help("Print the search results as JSON instead of a table")
+                .action(clap::ArgAction::SetTrue),
+        )
 }
 
 pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
@@ -50,6 +142,19 @@ pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::erro
             .collect::<Vec<_>>()
     });
 
+    let sort = matches.get_one::<String>("sort").unwrap().to_string();
+    let limit = *matches.get_one::<u32>("limit").unwrap();
+    let offset = match (
+        matches.get_one::<u32>("offset"),
+        matches.get_one::<u32>("page"),
+    ) {
+        (Some(offset), _) => *offset,
+        (None, Some(page)) => (page - 1)
+            .checked_mul(limit)
+            .ok_or_else(|| format!("Page {} is out of range.", page))?,
+        (None, None) => 0,
+    };
+
     let client = ModrinthClient::new()?;
 
     // Build facets JSON per Modrinth search API
@@ -65,18 +170,45 @@ pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::erro
             facets.push(vec![format!("versions:{}", gv)]);
         }
     }
+    // Separate inner lists are ANDed, so every requested category must match
+    if let Some(categories) = matches.get_many::<String>("category") {
+        for c in categories {
+            facets.push(vec![format!("categories:{}", c)]);
+        }
+    }
+    if matches.get_flag("server_only") {
+        // Values in one inner list are ORed; this drops `server_side:unsupported`
+        // (client-only) and `unknown` projects while keeping pagination exact
+        facets.push(vec![
+            "server_side:required".to_string(),
+            "server_side:optional".to_string(),
+        ]);
+    }
+    if let Some(support) = matches.get_one::<String>("server_side") {
+        facets.push(vec![format!("server_side:{}", support)]);
+    }
     let facets_str = serde_json::to_string(&facets)?;
 
     let query = SearchQuery {
         query: Some(query_str),
         facets: Some(facets_str),
-        index: None,
-        offset: None,
-        limit: Some(20),
+        index: Some(sort),
+        offset: Some(offset),
+        limit: Some(limit),
         filters: None,
     };
 
     let results = client.search_projects(Some(query)).await?;
+    // Resolve before printing so a bad index fails without output
+    let open = matches
+        .get_one::<String>("open")
+        .map(|target| resolve_open(target, &results.hits))
+        .transpose()?;
+    // --open conflicts with --json, so stdout stays parseable JSON
+    if matches.get_flag("json") {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+        return Ok(());
+    }
 
     let mut writer = std::io::stdout();
     let mut console = Console::from_fd(&mut writer);
@@ -84,6 +216,10 @@ pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::erro
     // Build table rows as Vec<Vec<Box<dyn Render>>> to match Table requirements
     let mut rows_owned: Vec<Vec<Box<dyn modern_terminal::core::render::Render>>> = Vec::new();
     rows_owned.push(vec![
+        {
+            let b: Box<dyn modern_terminal::core::render::Render> = header("#".to_string());
+            b
+        },
         {
             let b: Box<dyn modern_terminal::core::render::Render> = header("Title".to_string());
             b
@@ -96,9 +232,21 @@ pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::erro
             let b: Box<dyn modern_terminal::core::render::Render> = header("Author".to_string());
             b
         },
+        {
+            let b: Box<dyn modern_terminal::core::render::Render> = header("Client".to_string());
+            b
+        },
+        {
+            let b: Box<dyn modern_terminal::core::render::Render> = header("Server".to_string());
+            b
+        },
     ]);
-    for p in results.hits.iter() {
+    for (i, p) in results.hits.iter().enumerate() {
         rows_owned.push(vec![
+            {
+                let b: Box<dyn modern_terminal::core::render::Render> = field((i + 1).to_string());
+                b
+            },
             {
                 let b: Box<dyn modern_terminal::core::render::Render> = field(p.title.clone());
                 b
@@ -111,20 +259,128 @@ pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::erro
                 let b: Box<dyn modern_terminal::core::render::Render> = field(p.author.clone());
                 b
             },
+            {
+                let b: Box<dyn modern_terminal::core::render::Render> =
+                    field(p.client_side.clone());
+                b
+            },
+            {
+                let b: Box<dyn modern_terminal::core::render::Render> =
+                    field(p.server_side.clone());
+                b
+            },
         ]);
     }
 
     let component: Table = Table {
         column_sizes: vec![
+            Size::Cells(4),
             Size::Cells(20),
             Size::Cells(20),
-            Size::Cells(20),
-            Size::Cells(20),
+            Size::Cells(16),
+            Size::Cells(12),
+            Size::Cells(12),
         ],
         rows: rows_owned,
     };
 
     console.render(&component)?;
 
+    if results.hits.is_empty() {
+        println!(
+            "No results (offset {} of {})",
+            results.offset, results.total_hits
+        );
+    } else {
+        println!(
+            "Showing {}-{} of {}",
+            results.offset + 1,
+            results.offset + results.hits.len() as u32,
+            results.total_hits
+        );
+    }
+
+    if let Some(slug) = open {
+        browser::open_or_print(&project_url(&slug));
+    }
+
     Ok(())
 }
+
+/// Turn `--open` into a slug: a number picks that row of `hits` (1-based, as
+/// shown in the # column); anything else is taken as a slug
+fn resolve_open(target: &str, hits: &[ProjectResult]) -> Result<String, String> {
+    match target.parse::<usize>() {
+        Ok(n) => n
+            .checked_sub(1)
+            .and_then(|i| hits.get(i))
+            .map(|hit| hit.slug.clone())
+            .ok_or_else(|| format!("No result #{} on this page ({} shown)", n, hits.len())),
+        Err(_) => Ok(target.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_category_typo_suggests_known_category() {
+        let err = command()
+            .try_get_matches_from(["search", "lag", "--category", "optimisation"])
+            .unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::InvalidValue);
+        assert!(err.to_string().contains("optimization"), "{}", err);
+
+        let matches = command()
+            .try_get_matches_from(["search", "lag", "-c", "optimization", "-c", "utility"])
+            .unwrap();
+        let categories: Vec<_> = matches.get_many::<String>("category").unwrap().collect();
+        assert_eq!(categories, ["optimization", "utility"]);
+    }
+
+    #[test]
+    fn test_resolve_open() {
+        assert_eq!(resolve_open("sodium", &[]), Ok("sodium".to_string()));
+        assert_eq!(
+            resolve_open("2", &[]),
+            Err("No result #2 on this page (0 shown)".to_string())
+        );
+        assert!(resolve_open("0", &[]).is_err());
+    }
+
+    #[test]
+    fn test_server_side_takes_known_support_values() {
+        let matches = command()
+            .try_get_matches_from(["search", "map", "--server-side", "required"])
+            .unwrap();
+        assert_eq!(
+            matches.get_one::<String>("server_side").map(String::as_str),
+            Some("required")
+        );
+
+        let err = command()
+            .try_get_matches_from(["search", "map", "--server-side", "sometimes"])
+            .unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::InvalidValue);
+
+        let err = command()
+            .try_get_matches_from([
+                "search",
+                "map",
+                "--server-side",
+                "required",
+                "--server-only",
+            ])
+            .unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn test_open_conflicts_with_json() {
+        let err = command()
+            .try_get_matches_from(["search", "sodium", "--open", "1", "--json"])
+            .unwrap_err();
+        assert_eq!(err.kind(), clap::error::ErrorKind::ArgumentConflict);
+    }
+}
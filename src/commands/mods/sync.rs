@@ -0,0 +1,69 @@
+use crate::utils::checksum::sha512_hex;
+use crate::utils::config_file::{McConfig, ProjectKind};
+use crate::utils::download;
+use crate::utils::mod_lock::ModLock;
+use clap::Command;
+use std::fs;
+use std::path::PathBuf;
+use tracing::info;
+
+pub fn command() -> Command {
+    Command::new("sync")
+        .about("Install exactly the mod files pinned in mc.lock")
+        .arg(super::dir_arg())
+}
+
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    if !PathBuf::from("mc.lock").exists() {
+        return Err("mc.lock not found. Add mods first to create it.".into());
+    }
+    let lock = ModLock::load()?;
+    let mut config = McConfig::load()?;
+
+    let mods_dir = super::target_dir(matches, ProjectKind::Mod);
+    if !mods_dir.exists() {
+        fs::create_dir_all(&mods_dir)?;
+    }
+
+    let mut downloaded = 0usize;
+    for entry in lock.mods.iter() {
+        let target_path = mods_dir.join(&entry.filename);
+
+        // Keep files that already match the pinned hash
+        let up_to_date = match (fs::read(&target_path), entry.sha512.as_deref()) {
+            (Ok(existing), Some(expected)) => sha512_hex(&existing).eq_ignore_ascii_case(expected),
+            (Ok(_), None) => true,
+            (Err(_), _) => false,
+        };
+
+        if !up_to_date {
+            download::fetch_to_file(&entry.url, &target_path, entry.sha512.as_deref(), None)
+                .await
+                .map_err(|e| {
+                    format!(
+                        "Pinned file for '{}' could not be installed. {}",
+                        entry.slug, e
+                    )
+                })?;
+            info!(
+                "Downloaded: {} -> {}",
+                entry.filename,
+                target_path.display()
+            );
+            downloaded += 1;
+        }
+
+        config
+            .mods
+            .installed
+            .insert(entry.slug.clone(), entry.version_number.clone());
+    }
+
+    config.save("mc.toml")?;
+    info!(
+        "Synced {} mod(s) from mc.lock ({} downloaded).",
+        lock.mods.len(),
+        downloaded
+    );
+    Ok(())
+}
@@ -1,9 +1,15 @@
-use crate::libs::modrinth::ModrinthClient;
-use crate::utils::config_file::McConfig;
+use crate::commands::mods::add::latest_compatible;
+use crate::libs::modrinth::{CONCURRENT_LOOKUPS, ModrinthClient, Version, find_version};
+use crate::utils::config_file::{McConfig, ProjectKind};
+use crate::utils::download;
+use crate::utils::mod_lock::{LockedMod, ModLock};
 use clap::{Arg, Command};
+use futures::stream::{self, StreamExt};
+use indicatif::MultiProgress;
 use std::fs;
 use std::io::{self, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
 
 extern crate modern_terminal;
 use crate::utils::console_log::{field, header};
@@ -15,6 +21,7 @@ use modern_terminal::{
 pub fn command() -> Command {
     Command::new("update")
         .about("Check installed mods against latest and update like dnf")
+        .arg(super::dir_arg())
         .arg(
             Arg::new("yes")
                 .long("yes")
@@ -22,15 +29,76 @@ pub fn command() -> Command {
                 .help("Assume yes; update without confirmation")
                 .action(clap::ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("dry_run")
+                .long("dry-run")
+                .help("Only show pending updates; exit non-zero if any are available")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("json")
+                .long("json")
+                .help("Print the update candidates as JSON and exit without updating")
+                .action(clap::ArgAction::SetTrue),
+        )
 }
 
-struct UpdateCandidate {
+/// How an installed mod compares to the latest compatible version on Modrinth
+pub(super) struct UpdateCandidate {
     slug: String,
     installed: String,
     latest: String,
+    /// Whether `latest` was published after the installed version
+    newer: bool,
     old_filename: Option<String>,
     new_filename: Option<String>,
     new_url: Option<String>,
+    new_sha512: Option<String>,
+    new_version_id: Option<String>,
+}
+
+/// How an installed mod compares to the latest compatible version
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum UpdateStatus {
+    /// The lookup failed, so there is nothing to compare against
+    Unknown,
+    UpToDate,
+    Available,
+    /// The installed version is newer than, or missing from, the compatible
+    /// versions, so updating would not move it forward
+    Pinned,
+}
+
+impl UpdateStatus {
+    /// Label used in the table and JSON output
+    pub(super) fn as_str(&self) -> &'static str {
+        match self {
+            UpdateStatus::Unknown => "unknown",
+            UpdateStatus::UpToDate => "up-to-date",
+            UpdateStatus::Available => "update available",
+            UpdateStatus::Pinned => "pinned",
+        }
+    }
+}
+
+impl UpdateCandidate {
+    /// How the installed version compares to the latest
+    pub(super) fn status(&self) -> UpdateStatus {
+        if self.latest == "-" {
+            UpdateStatus::Unknown
+        } else if self.latest == self.installed {
+            UpdateStatus::UpToDate
+        } else if self.newer {
+            UpdateStatus::Available
+        } else {
+            UpdateStatus::Pinned
+        }
+    }
+
+    /// Whether a newer compatible version than the installed one exists
+    pub(super) fn has_update(&self) -> bool {
+        self.status() == UpdateStatus::Available
+    }
 }
 
 pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
@@ -38,64 +106,167 @@ pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::erro
 
     let mut config = McConfig::load()?;
     let client = ModrinthClient::new()?;
+    let candidates = collect_candidates(&client, &config).await;
 
-    // Collect update candidates
-    let mut candidates: Vec<UpdateCandidate> = Vec::new();
-    for (slug, installed_version) in config.mods.installed.clone().into_iter() {
-        let versions = client.get_project_versions(&slug).await;
-        let mut latest_version = String::from("-");
-        let mut new_file_url: Option<String> = None;
-        let mut new_filename: Option<String> = None;
-        let mut old_filename: Option<String> = None;
-
-        match versions {
-            Ok(vs) => {
-                // Determine latest (first entry)
-                if let Some(v) = vs.first() {
-                    latest_version = v.version_number.clone().unwrap_or_else(|| v.id.clone());
-                    if let Some(file) = v
-                        .files
-                        .iter()
-                        .find(|f| f.primary.unwrap_or(false))
-                        .or_else(|| v.files.first())
-                    {
-                        new_file_url = Some(file.url.clone());
-                        new_filename = Some(file.filename.clone());
-                    }
-                }
-                // Determine old filename to delete
-                for v in vs.iter() {
-                    if v.version_number.as_deref() == Some(installed_version.as_str())
-                        || v.id == installed_version
-                    {
-                        if let Some(file) = v
-                            .files
-                            .iter()
-                            .find(|f| f.primary.unwrap_or(false))
-                            .or_else(|| v.files.first())
-                        {
-                            old_filename = Some(file.filename.clone());
-                        }
-                        break;
-                    }
-                }
+    if matches.get_flag("json") {
+        let entries: Vec<serde_json::Value> = candidates
+            .iter()
+            .map(|c| {
+                serde_json::json!({
+                    "slug": c.slug,
+                    "installed": c.installed,
+                    "latest": (c.latest != "-").then_some(&c.latest),
+                    "status": c.status().as_str(),
+                    "filename": c.new_filename,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    render_table(&candidates, |c| c.status().as_str())?;
+    let updates_available = candidates.iter().filter(|c| c.has_update()).count();
+
+    if updates_available == 0 {
+        info!("All mods are up-to-date.");
+        return Ok(());
+    }
+    if matches.get_flag("dry_run") {
+        return Err(format!("{} update(s) available", updates_available).into());
+    }
+
+    // Confirm update unless -y
+    let proceed = if assume_yes {
+        true
+    } else {
+        print!("Proceed to update {} mod(s)? [y/N] ", updates_available);
+        io::stdout().flush()?;
+        let mut input = String::new();
+        let read = io::stdin().read_line(&mut input)?;
+        if read == 0 {
+            false
+        } else {
+            matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
+        }
+    };
+
+    if !proceed {
+        info!("Update cancelled.");
+        return Ok(());
+    }
+
+    // Ensure mods directory exists
+    let mods_dir = super::target_dir(matches, ProjectKind::Mod);
+    if !mods_dir.exists() {
+        fs::create_dir_all(&mods_dir)?;
+    }
+
+    // Download and verify every new jar concurrently before touching any old one
+    let pending: Vec<UpdateCandidate> = candidates.into_iter().filter(|c| c.has_update()).collect();
+    let progress = MultiProgress::new();
+    let downloads: Vec<(UpdateCandidate, Result<PathBuf, String>)> = stream::iter(pending)
+        .map(|c| {
+            let progress = &progress;
+            let mods_dir = &mods_dir;
+            async move {
+                let part = download_verified(&c, mods_dir, progress).await;
+                (c, part)
             }
-            Err(_) => {
-                // Leave latest as "-" if query failed
+        })
+        .buffered(CONCURRENT_LOOKUPS)
+        .collect()
+        .await;
+
+    // Swap jars only for verified downloads; failed ones keep their old jar and entry
+    let mut lock = ModLock::load()?;
+    let mut updated = 0usize;
+    let mut failed = 0usize;
+    for (c, part) in downloads.into_iter() {
+        let part = match part {
+            Ok(p) => p,
+            Err(e) => {
+                warn!("Skipping {}: {}", c.slug, e);
+                failed += 1;
+                continue;
+            }
+        };
+        let Some(new_fn) = c.new_filename.as_ref() else {
+            continue;
+        };
+        let new_path = mods_dir.join(new_fn);
+        if let Err(e) = download::finish_part(&part, &new_path) {
+            warn!(
+                "Skipping {}: could not write {} ({})",
+                c.slug,
+                new_path.display(),
+                e
+            );
+            failed += 1;
+            continue;
+        }
+        info!("Downloaded new jar: {}", new_path.display());
+
+        // Delete old jar if we know the filename
+        if let Some(old_fn) = c.old_filename.as_ref().filter(|f| *f != new_fn) {
+            let old_path = mods_dir.join(old_fn);
+            if old_path.exists() {
+                let _ = fs::remove_file(&old_path);
+                info!("Removed old jar: {}", old_path.display());
             }
         }
 
-        let _needs_update = !latest_version.eq(&installed_version) && latest_version != "-";
-        candidates.push(UpdateCandidate {
-            slug,
-            installed: installed_version,
-            latest: latest_version,
-            old_filename,
-            new_filename,
-            new_url: new_file_url,
-        });
+        // Update config and lockfile
+        config
+            .mods
+            .installed
+            .insert(c.slug.clone(), c.latest.clone());
+        if let (Some(version_id), Some(filename), Some(url)) =
+            (c.new_version_id, c.new_filename, c.new_url)
+        {
+            lock.upsert(LockedMod {
+                slug: c.slug.clone(),
+                version_id,
+                version_number: c.latest.clone(),
+                filename,
+                url,
+                sha512: c.new_sha512,
+            });
+        }
+        updated += 1;
     }
 
+    // Save updated config
+    config.save("mc.toml")?;
+    lock.save("mc.lock")?;
+    info!("Updated {} mod(s).", updated);
+    if failed > 0 {
+        return Err(format!("{} mod(s) failed to update", failed).into());
+    }
+
+    Ok(())
+}
+
+/// Look up every installed mod, sorted by slug, a few lookups at a time
+pub(super) async fn collect_candidates(
+    client: &ModrinthClient,
+    config: &McConfig,
+) -> Vec<UpdateCandidate> {
+    let mut installed: Vec<(String, String)> = config.mods.installed.clone().into_iter().collect();
+    installed.sort();
+    // `buffered` keeps input order
+    stream::iter(installed)
+        .map(|(slug, installed_version)| check_update(client, config, slug, installed_version))
+        .buffered(CONCURRENT_LOOKUPS)
+        .collect()
+        .await
+}
+
+/// Print installed vs latest versions, with `status` describing each row
+pub(super) fn render_table(
+    candidates: &[UpdateCandidate],
+    status: impl Fn(&UpdateCandidate) -> &'static str,
+) -> Result<(), Box<dyn std::error::Error>> {
     // Render table showing diffs
     let mut rows: Vec<Vec<Box<dyn modern_terminal::core::render::Render>>> = Vec::new();
     rows.push(vec![
@@ -116,16 +287,7 @@ pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::erro
             b
         },
     ]);
-    let mut updates_available = 0usize;
     for c in candidates.iter() {
-        let status = if c.latest == "-" {
-            "unknown"
-        } else if c.latest == c.installed {
-            "up-to-date"
-        } else {
-            updates_available += 1;
-            "update available"
-        };
         rows.push(vec![
             {
                 let b: Box<dyn modern_terminal::core::render::Render> = field(c.slug.clone());
@@ -140,7 +302,8 @@ pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::erro
                 b
             },
             {
-                let b: Box<dyn modern_terminal::core::render::Render> = field(status.to_string());
+                let b: Box<dyn modern_terminal::core::render::Render> =
+                    field(status(c).to_string());
                 b
             },
         ]);
@@ -158,76 +321,148 @@ pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::erro
     let mut writer = std::io::stdout();
     let mut console = Console::from_fd(&mut writer);
     console.render(&component)?;
+    Ok(())
+}
 
-    if updates_available == 0 {
-        println!("All mods are up-to-date.");
-        return Ok(());
-    }
+/// Download the new jar of a candidate to a `.part` file in `mods_dir` and check it
+/// against the published hash
+async fn download_verified(
+    c: &UpdateCandidate,
+    mods_dir: &Path,
+    progress: &MultiProgress,
+) -> Result<PathBuf, String> {
+    let (url, filename) = c
+        .new_url
+        .as_ref()
+        .zip(c.new_filename.as_ref())
+        .ok_or("no file info")?;
+    download::fetch_part(
+        url,
+        &mods_dir.join(filename),
+        c.new_sha512.as_deref(),
+        Some(progress),
+    )
+    .await
+    .map_err(|e| format!("could not download '{}' ({})", filename, e))
+}
 
-    // Confirm update unless -y
-    let proceed = if assume_yes {
-        true
-    } else {
-        print!("Proceed to update {} mod(s)? [y/N] ", updates_available);
-        io::stdout().flush()?;
-        let mut input = String::new();
-        let read = io::stdin().read_line(&mut input)?;
-        if read == 0 {
-            false
-        } else {
-            matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
-        }
-    };
+/// Look up the latest version of an installed mod that fits the configured game and loader
+async fn check_update(
+    client: &ModrinthClient,
+    config: &McConfig,
+    slug: String,
+    installed_version: String,
+) -> UpdateCandidate {
+    let versions = client.get_project_versions(&slug).await;
+    let mut latest_version = String::from("-");
+    let mut newer = false;
+    let mut new_file_url: Option<String> = None;
+    let mut new_filename: Option<String> = None;
+    let mut new_sha512: Option<String> = None;
+    let mut new_version_id: Option<String> = None;
+    let mut old_filename: Option<String> = None;
 
-    if !proceed {
-        println!("Update cancelled.");
-        return Ok(());
+    match versions {
+        Ok(vs) => {
+            // Determine latest compatible (newest first)
+            if let Some(v) = latest_compatible(config, ProjectKind::Mod, &vs) {
+                latest_version = v.version_number.clone().unwrap_or_else(|| v.id.clone());
+                newer = is_newer(&vs, v, &installed_version);
+                new_version_id = Some(v.id.clone());
+                if let Some(file) = v
+                    .files
+                    .iter()
+                    .find(|f| f.primary.unwrap_or(false))
+                    .or_else(|| v.files.first())
+                {
+                    new_file_url = Some(file.url.clone());
+                    new_filename = Some(file.filename.clone());
+                    new_sha512 = file.hashes.sha512.clone();
+                }
+            }
+            // Determine old filename to delete
+            if let Some(v) = find_version(&vs, &installed_version)
+                && let Some(file) = v
+                    .files
+                    .iter()
+                    .find(|f| f.primary.unwrap_or(false))
+                    .or_else(|| v.files.first())
+            {
+                old_filename = Some(file.filename.clone());
+            }
+        }
+        Err(_) => {
+            // Leave latest as "-" if query failed
+        }
     }
 
-    // Ensure mods directory exists
-    let mods_dir = PathBuf::from("mods");
-    if !mods_dir.exists() {
-        fs::create_dir_all(&mods_dir)?;
+    UpdateCandidate {
+        slug,
+        installed: installed_version,
+        latest: latest_version,
+        newer,
+        old_filename,
+        new_filename,
+        new_url: new_file_url,
+        new_sha512,
+        new_version_id,
     }
+}
 
-    // Perform updates
-    let mut updated = 0usize;
-    for c in candidates.into_iter() {
-        if c.latest == "-" || c.latest == c.installed {
-            continue;
-        }
-
-        // Delete old jar if we know the filename
-        if let Some(old_fn) = c.old_filename.as_ref() {
-            let old_path = mods_dir.join(old_fn);
-            if old_path.exists() {
-                let _ = fs::remove_file(&old_path);
-                println!("Removed old jar: {}", old_path.display());
-            }
-        }
+/// Whether `latest` is newer than the installed version: by publish date when both
+/// have one, else by position in `versions` (Modrinth lists newest first). A
+/// version that is not in the list cannot be ordered and is never replaced.
+fn is_newer(versions: &[Version], latest: &Version, installed: &str) -> bool {
+    let Some(current) = find_version(versions, installed) else {
+        return false;
+    };
+    if let (Some(latest_date), Some(current_date)) = (
+        latest.date_published.as_deref(),
+        current.date_published.as_deref(),
+    ) {
+        return latest_date > current_date;
+    }
+    let position = |v: &Version| versions.iter().position(|o| o.id == v.id);
+    matches!((position(latest), position(current)), (Some(l), Some(c)) if l < c)
+}
 
-        // Download new jar
-        if let (Some(url), Some(new_fn)) = (c.new_url.as_ref(), c.new_filename.as_ref()) {
-            let bytes = reqwest::get(url).await?.bytes().await?;
-            let new_path = mods_dir.join(new_fn);
-            fs::write(&new_path, &bytes)?;
-            println!("Downloaded new jar: {}", new_path.display());
-        } else {
-            println!("Skipping download for {}: no file info.", c.slug);
-            continue;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        // Update config
-        config
-            .mods
-            .installed
-            .insert(c.slug.clone(), c.latest.clone());
-        updated += 1;
+    fn version(id: &str, date: Option<&str>) -> Version {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "project_id": "p",
+            "name": null,
+            "version_number": id,
+            "game_versions": [],
+            "loaders": ["fabric"],
+            "files": [],
+            "date_published": date,
+        }))
+        .unwrap()
     }
 
-    // Save updated config
-    config.save("mc.toml")?;
-    println!("Updated {} mod(s).", updated);
+    #[test]
+    fn test_is_newer_never_downgrades() {
+        // Newest first, as returned by Modrinth
+        let versions = vec![
+            version("3.0.0", None),
+            version("2.0.0", None),
+            version("1.0.0", None),
+        ];
+        assert!(is_newer(&versions, &versions[1], "1.0.0"));
+        // Installed 3.0.0 is newer than the latest compatible 2.0.0
+        assert!(!is_newer(&versions, &versions[1], "3.0.0"));
+        // A pinned version Modrinth does not list cannot be ordered
+        assert!(!is_newer(&versions, &versions[0], "9.9.9"));
 
-    Ok(())
+        let dated = vec![
+            version("2.0.0", Some("2024-01-01T00:00:00Z")),
+            version("1.1.0", Some("2024-03-01T00:00:00Z")),
+        ];
+        assert!(is_newer(&dated, &dated[1], "2.0.0"));
+        assert!(!is_newer(&dated, &dated[0], "1.1.0"));
+    }
 }
@@ -0,0 +1,72 @@
+use crate::utils::mc_format;
+use crate::utils::rcon::send_command;
+use clap::{Arg, Command};
+use tracing::info;
+
+/// Build the op subcommand definition
+pub fn command() -> Command {
+    Command::new("op")
+        .about("Make a player a server operator via RCON")
+        .arg(player_arg())
+}
+
+/// Build the deop subcommand definition
+pub fn deop_command() -> Command {
+    Command::new("deop")
+        .about("Revoke a player's operator status via RCON")
+        .arg(player_arg())
+}
+
+// The name goes to the server verbatim; it resolves offline-mode UUIDs itself
+fn player_arg() -> Arg {
+    Arg::new("player")
+        .help("Player name")
+        .required(true)
+        .index(1)
+}
+
+/// Execute the op subcommand
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    run("op", matches.get_one::<String>("player").unwrap()).await
+}
+
+/// Execute the deop subcommand
+pub async fn execute_deop(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    run("deop", matches.get_one::<String>("player").unwrap()).await
+}
+
+async fn run(action: &str, player: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let rcon_cmd = format!("{} {}", action, player);
+    let reply = send_command(&rcon_cmd)
+        .await
+        .map_err(|e| format!("Failed to run '{}' via RCON: {}", rcon_cmd, e))?;
+    let reply = mc_format::strip_codes(&reply);
+    if changed(&reply) {
+        info!("{}", reply);
+        Ok(())
+    } else {
+        Err(format!("{} {} failed: {}", action, player, reply.trim()).into())
+    }
+}
+
+/// Whether the server reports a change, e.g. "Made Steve a server operator".
+/// "Nothing changed..." and unknown-player replies count as failures.
+fn changed(reply: &str) -> bool {
+    reply.trim_start().starts_with("Made ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed() {
+        assert!(changed("Made Steve a server operator"));
+        assert!(changed("Made Steve no longer a server operator"));
+        assert!(!changed(
+            "Nothing changed. The player already is an operator"
+        ));
+        assert!(!changed("That player does not exist"));
+        assert!(!changed(""));
+    }
+}
@@ -1,6 +1,7 @@
-use crate::utils::mc_server_props::ServerProperties;
+use crate::utils::mc_server_props::{PropType, ServerProperties};
 use clap::Command;
 use std::path::PathBuf;
+use tracing::{info, warn};
 
 /// Build the props subcommand
 pub fn command() -> Command {
@@ -10,7 +11,7 @@ pub fn command() -> Command {
             clap::Arg::new("key")
                 .value_name("KEY")
                 .help("Property key to read or set")
-                .required(true),
+                .required_unless_present("list"),
         )
         .arg(
             clap::Arg::new("value")
@@ -26,29 +27,73 @@ pub fn command() -> Command {
                 .help("Path to server.properties (defaults to ./server.properties)")
                 .required(false),
         )
+        .arg(
+            clap::Arg::new("list")
+                .long("list")
+                .short('l')
+                .help("List all properties in sorted order")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with_all(["key", "value"]),
+        )
+        .arg(
+            clap::Arg::new("delete")
+                .long("delete")
+                .short('d')
+                .help("Remove the given key from server.properties")
+                .action(clap::ArgAction::SetTrue)
+                .requires("key")
+                .conflicts_with("value"),
+        )
 }
 
 /// Execute the props subcommand
 pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
-    let key = matches.get_one::<String>("key").unwrap().to_string();
-    let value = matches.get_one::<String>("value").cloned();
-
     let path = matches
         .get_one::<String>("file")
         .map(PathBuf::from)
         .unwrap_or_else(|| PathBuf::from("server.properties"));
     let mut props = ServerProperties::from_file(&path)?;
 
+    if matches.get_flag("list") {
+        let mut entries: Vec<(&str, &str)> = props.entries().collect();
+        entries.sort();
+        for (k, v) in entries {
+            println!("{}={}", k, v);
+        }
+        return Ok(());
+    }
+
+    let key = matches.get_one::<String>("key").unwrap().to_string();
+    let value = matches.get_one::<String>("value").cloned();
+
+    if matches.get_flag("delete") {
+        if !props.remove(&key) {
+            return Err(format!("Key '{}' not found in server.properties", key).into());
+        }
+        props.save(&path)?;
+        info!("Removed '{}' from {}", key, path.display());
+        return Ok(());
+    }
+
     match value {
         Some(v) => {
+            match PropType::for_key(&key) {
+                Some(t) => t
+                    .validate(&v)
+                    .map_err(|e| format!("Invalid value for '{}': {}", key, e))?,
+                None => warn!(
+                    "'{}' is not a known property; saving without validation",
+                    key
+                ),
+            }
             props.set(&key, v.clone());
             props.save(&path)?;
-            println!("{}={}", key, v);
+            info!("{}={}", key, v);
         }
         None => match props.get(&key) {
             Some(v) => println!("{}", v),
             None => {
-                eprintln!("Key '{}' not found in server.properties", key);
+                return Err(format!("Key '{}' not found in server.properties", key).into());
             }
         },
     }
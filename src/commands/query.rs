@@ -0,0 +1,51 @@
+use crate::utils::mc_server_props::ServerProperties;
+use crate::utils::slp;
+use clap::{Arg, Command};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Build the query subcommand definition
+pub fn command() -> Command {
+    Command::new("query")
+        .about("Query MOTD, version and player count over the game port (no RCON needed)")
+        .arg(
+            Arg::new("timeout")
+                .long("timeout")
+                .short('t')
+                .value_name("SECS")
+                .help("Seconds to wait for the server to answer")
+                .value_parser(clap::value_parser!(u64))
+                .default_value("5"),
+        )
+}
+
+/// Execute the query subcommand
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let timeout = Duration::from_secs(*matches.get_one::<u64>("timeout").unwrap());
+    let (host, port) = get_game_address();
+
+    let status = slp::query(&host, port, timeout)
+        .await
+        .map_err(|e| format!("Could not query {}:{} ({})", host, port, e))?;
+
+    println!("Version: {}", status.version);
+    println!("Players: {}/{}", status.players_online, status.players_max);
+    println!("MOTD:    {}", status.motd);
+    Ok(())
+}
+
+/// Game address from server.properties: `server-ip` (default localhost) and `server-port`
+fn get_game_address() -> (String, u16) {
+    let props = ServerProperties::from_file(PathBuf::from("server.properties")).ok();
+    let host = props
+        .as_ref()
+        .and_then(|p| p.get("server-ip"))
+        .filter(|h| !h.trim().is_empty())
+        .unwrap_or_else(|| "127.0.0.1".to_string());
+    let port = props
+        .as_ref()
+        .and_then(|p| p.get("server-port"))
+        .and_then(|p| p.trim().parse::<u16>().ok())
+        .unwrap_or(25565);
+    (host, port)
+}
@@ -0,0 +1,123 @@
+use crate::commands::init::select_with_ratatui;
+use crate::utils::pid_file;
+use crate::utils::world::{BACKUP_DIR, level_name, list_backups, timestamp};
+use clap::{Arg, Command};
+use flate2::read::GzDecoder;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use tracing::info;
+
+/// Staging directory the archive is unpacked into before it replaces the world
+const STAGING_DIR: &str = ".restore-tmp";
+
+/// Build the restore subcommand definition
+pub fn command() -> Command {
+    Command::new("restore")
+        .about("Replace the world directory with a backup from backups/")
+        .arg(
+            Arg::new("file")
+                .long("file")
+                .short('f')
+                .value_name("PATH")
+                .help("Backup archive to restore instead of picking one"),
+        )
+        .arg(
+            Arg::new("yes")
+                .long("yes")
+                .short('y')
+                .help("Assume yes; restore without confirmation")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+/// Execute the restore subcommand
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(pid) = pid_file::running_pid() {
+        return Err(format!(
+            "Server is running (PID {}). Stop it before restoring a backup.",
+            pid
+        )
+        .into());
+    }
+
+    let archive = match matches.get_one::<String>("file") {
+        Some(f) => PathBuf::from(f),
+        None => {
+            // Newest first, since that is usually the one wanted
+            let mut backups = list_backups()?;
+            backups.reverse();
+            if backups.is_empty() {
+                return Err(format!("No backups found in {}/", BACKUP_DIR).into());
+            }
+            let labels: Vec<String> = backups.iter().map(|p| p.display().to_string()).collect();
+            let Some(idx) = select_with_ratatui("Select Backup", &labels)? else {
+                info!("Restore cancelled.");
+                return Ok(());
+            };
+            backups.swap_remove(idx)
+        }
+    };
+    if !archive.is_file() {
+        return Err(format!("Backup '{}' not found", archive.display()).into());
+    }
+
+    let world = level_name();
+    if !matches.get_flag("yes") {
+        print!(
+            "Replace '{}' with {}? The current world is kept aside. [y/N] ",
+            world,
+            archive.display()
+        );
+        io::stdout().flush()?;
+        let mut input = String::new();
+        let read = io::stdin().read_line(&mut input)?;
+        if read == 0 || !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+            info!("Restore cancelled.");
+            return Ok(());
+        }
+    }
+
+    // Unpack into a staging directory first so a bad archive leaves the world untouched
+    let staging = Path::new(STAGING_DIR);
+    if staging.exists() {
+        fs::remove_dir_all(staging)?;
+    }
+    let restored = match unpack(&archive, staging) {
+        Ok(dir) => dir,
+        Err(e) => {
+            let _ = fs::remove_dir_all(staging);
+            return Err(e);
+        }
+    };
+
+    if Path::new(&world).exists() {
+        let aside = format!("{}.pre-restore-{}", world, timestamp(SystemTime::now()));
+        fs::rename(&world, &aside)?;
+        info!("Moved current world to {}", aside);
+    }
+    fs::rename(&restored, &world)?;
+    let _ = fs::remove_dir_all(staging);
+
+    info!("Restored '{}' from {}", world, archive.display());
+    Ok(())
+}
+
+/// Extract a backup into `staging`, returning the single world directory it contains
+fn unpack(archive: &Path, staging: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    fs::create_dir_all(staging)?;
+    tar::Archive::new(GzDecoder::new(File::open(archive)?)).unpack(staging)?;
+
+    let mut dirs: Vec<PathBuf> = fs::read_dir(staging)?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .collect();
+    match (dirs.pop(), dirs.is_empty()) {
+        (Some(dir), true) if dir.is_dir() => Ok(dir),
+        _ => Err(format!(
+            "Backup '{}' does not contain a single world directory",
+            archive.display()
+        )
+        .into()),
+    }
+}
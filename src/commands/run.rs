@@ -1,8 +1,19 @@
 use crate::utils::config_file::McConfig;
+use crate::utils::java;
+use crate::utils::mc_server_props::ServerProperties;
+use crate::utils::pid_file::{self, PID_FILE};
+use crate::utils::process::terminate;
+use crate::utils::rcon::{RconClient, resolve_rcon_config, send_command};
 use crate::utils::runner::{run_cmd, run_cmd_with_io};
+use chrono::{Local, NaiveDateTime, NaiveTime, TimeZone};
 use clap::{Arg, Command};
 use std::fs;
+use std::net::TcpListener;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
 
 /// Build the run subcommand definition
 pub fn command() -> Command {
@@ -21,6 +32,151 @@ pub fn command() -> Command {
                 .help("Run server in background (demon mode)")
                 .action(clap::ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("restart_on_crash")
+                .long("restart-on-crash")
+                .help("Relaunch the server with backoff when it exits with an error")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with("demon"),
+        )
+        .arg(
+            Arg::new("max_restarts")
+                .long("max-restarts")
+                .value_name("N")
+                .help("Maximum number of automatic restarts")
+                .value_parser(clap::value_parser!(u32))
+                .default_value("5")
+                .requires("restart_on_crash"),
+        )
+        .arg(
+            Arg::new("restart_at")
+                .long("restart-at")
+                .value_name("HH:MM")
+                .help("Restart the server at this local time each day, after an in-game countdown; repeatable")
+                .value_parser(parse_clock_time)
+                .action(clap::ArgAction::Append)
+                .conflicts_with("demon"),
+        )
+        .arg(
+            Arg::new("java")
+                .long("java")
+                .value_name("PATH")
+                .help("Java binary to launch with, overriding console.java_path"),
+        )
+        .arg(
+            Arg::new("strict")
+                .long("strict")
+                .help("Fail instead of warning when Java is missing or too old")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("force")
+                .long("force")
+                .help("Start even if a server is already running or the server port is in use")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+/// Delay before the given restart attempt (0-based): 1s, 2s, 4s, ... capped at 60s
+fn restart_delay(attempt: u32) -> Duration {
+    Duration::from_secs(1u64.checked_shl(attempt).unwrap_or(u64::MAX).min(60))
+}
+
+/// Uptime after which a crash is treated as a fresh failure and the backoff starts over
+const HEALTHY_UPTIME: Duration = Duration::from_secs(300);
+
+/// Whether an exit code means the server was stopped on purpose rather than crashing.
+/// `None` is a signal exit on Unix; 130/137/143 are the JVM's codes for SIGINT/SIGKILL/SIGTERM.
+fn stopped_deliberately(code: Option<i32>) -> bool {
+    matches!(code, None | Some(0) | Some(130) | Some(137) | Some(143))
+}
+
+/// Seconds before a scheduled restart at which players are warned
+const RESTART_WARNINGS: &[u64] = &[300, 60, 30, 10];
+
+/// Parse a 24-hour `HH:MM` wall-clock time
+fn parse_clock_time(value: &str) -> Result<NaiveTime, String> {
+    NaiveTime::parse_from_str(value, "%H:%M").map_err(|_| {
+        format!(
+            "'{}' is not a valid time; expected HH:MM, e.g. 04:30",
+            value
+        )
+    })
+}
+
+/// The first of `times` strictly after `now`, today or tomorrow
+fn next_restart(now: NaiveDateTime, times: &[NaiveTime]) -> Option<NaiveDateTime> {
+    times
+        .iter()
+        .map(|t| {
+            let at = now.date().and_time(*t);
+            if at > now {
+                at
+            } else {
+                at + chrono::Duration::days(1)
+            }
+        })
+        .min()
+}
+
+/// Time until the next restart in local time. A time skipped by a DST change
+/// falls back to the wall-clock difference.
+fn until_next_restart(times: &[NaiveTime]) -> Option<Duration> {
+    let now = Local::now();
+    let next = next_restart(now.naive_local(), times)?;
+    let wait = match Local.from_local_datetime(&next).earliest() {
+        Some(at) => at.signed_duration_since(now),
+        None => next - now.naive_local(),
+    };
+    Some(wait.to_std().unwrap_or_default())
+}
+
+/// "5 minutes", "1 minute", "30 seconds"
+fn countdown_label(secs: u64) -> String {
+    match secs {
+        60 => "1 minute".to_string(),
+        s if s % 60 == 0 => format!("{} minutes", s / 60),
+        1 => "1 second".to_string(),
+        s => format!("{} seconds", s),
+    }
+}
+
+/// Wait `wait`, warning players over RCON on the way, then stop the server:
+/// via RCON `stop`, or by terminating `pid` if RCON is unavailable. `stopping`
+/// is set before the server is told to stop, so the exit that follows is known
+/// to be a scheduled restart.
+async fn scheduled_restart(wait: Duration, pid: u32, stopping: Arc<AtomicBool>) {
+    let mut remaining = wait;
+    for &warning in RESTART_WARNINGS {
+        let at = Duration::from_secs(warning);
+        if remaining <= at {
+            continue;
+        }
+        tokio::time::sleep(remaining - at).await;
+        remaining = at;
+        let message = format!("say Server restarting in {}", countdown_label(warning));
+        if let Err(e) = send_command(&message).await {
+            warn!("could not announce the restart via RCON ({})", e);
+        }
+    }
+    tokio::time::sleep(remaining).await;
+
+    info!("Scheduled restart: stopping the server...");
+    stopping.store(true, Ordering::SeqCst);
+    let (host, port, password) = resolve_rcon_config("server.properties");
+    match RconClient::connect(&host, port, &password).await {
+        // The server usually drops the connection while shutting down, before
+        // the reply arrives; its exit is the real success check
+        Ok(mut client) => {
+            let _ = client.cmd("stop").await;
+        }
+        Err(e) => {
+            warn!("RCON unavailable ({}); terminating PID {}", e, pid);
+            if let Err(e) = terminate(&pid.to_string()) {
+                warn!("could not terminate PID {}: {}", pid, e);
+            }
+        }
+    }
 }
 
 /// Execute the run subcommand
@@ -30,7 +186,32 @@ pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::erro
     let demon_mode = matches.get_flag("demon");
 
     // Build launch command from config.console.launch_cmd
-    let mut cmd_args: Vec<String> = config.console.launch_cmd.clone();
+    let java_override = matches.get_one::<String>("java").map(String::as_str);
+    let mut cmd_args: Vec<String> = config.console.command(java_override);
+
+    // Only check launchers that are a JVM, not wrapper scripts
+    if let Some(program) = cmd_args.first()
+        && (java::is_java(program) || java_override == Some(program.as_str()))
+    {
+        java::check(
+            program,
+            &config.versions.mc_version,
+            matches.get_flag("strict"),
+        )?;
+    }
+
+    if !matches.get_flag("force") {
+        // A live PID means another server would write to the same world
+        if let Some(pid) = pid_file::running_pid() {
+            return Err(format!(
+                "Server is already running (PID {}). Stop it first or use --force to start anyway.",
+                pid
+            )
+            .into());
+        }
+        check_port_free()?;
+    }
+
     if matches.get_flag("nogui") && !cmd_args.iter().any(|a| a == "nogui") {
         cmd_args.push("nogui".to_string());
     }
@@ -42,28 +223,149 @@ pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::erro
         // Background mode: do not inherit IO, do not wait
         let child = run_cmd_with_io(&cmd_slice, false).await?;
         let pid = child.id();
-        fs::write(PathBuf::from("mc.lock"), format!("{}\n", pid))?;
-        println!(
-            "Server started in background. PID {} stored in mc.lock",
-            pid
+        pid_file::record_start(pid, &cmd_slice)?;
+        info!(
+            "Server started in background. PID {} stored in {}",
+            pid, PID_FILE
         );
     } else {
-        // Foreground mode: inherit IO and wait for exit
-        let mut child = run_cmd(&cmd_slice).await?;
-        let pid = child.id();
-        fs::write(PathBuf::from("mc.lock"), format!("{}\n", pid))?;
-        println!(
-            "Server started in foreground. PID {} stored in mc.lock",
-            pid
-        );
+        let restart_on_crash = matches.get_flag("restart_on_crash");
+        let max_restarts = *matches.get_one::<u32>("max_restarts").unwrap();
+        let restart_times: Vec<NaiveTime> = matches
+            .get_many::<NaiveTime>("restart_at")
+            .map(|t| t.copied().collect())
+            .unwrap_or_default();
+        let mut restarts = 0u32;
 
-        let status = child.wait()?;
-        println!("Server exited with status: {}", status);
+        loop {
+            // Foreground mode: inherit IO and wait for exit
+            let mut child = run_cmd(&cmd_slice).await?;
+            let pid = child.id();
+            pid_file::record_start(pid, &cmd_slice)?;
+            info!(
+                "Server started in foreground. PID {} stored in {}",
+                pid, PID_FILE
+            );
 
-        // Remove mc.lock when server stops
-        let _ = fs::remove_file(PathBuf::from("mc.lock"));
-        println!("mc.lock removed");
+            let stopping = Arc::new(AtomicBool::new(false));
+            let schedule = until_next_restart(&restart_times).map(|wait| {
+                info!("Next scheduled restart in {}m", wait.as_secs() / 60);
+                tokio::spawn(scheduled_restart(wait, pid, Arc::clone(&stopping)))
+            });
+
+            // Wait off the async workers so the schedule task always gets to run
+            let started = Instant::now();
+            let status = tokio::task::spawn_blocking(move || child.wait()).await??;
+            info!("Server exited with status: {}", status);
+
+            // The task may still be waiting on RCON; the flag says whether it stopped the server
+            if let Some(task) = schedule {
+                task.abort();
+                if stopping.load(Ordering::SeqCst) {
+                    info!("Relaunching after scheduled restart...");
+                    restarts = 0;
+                    continue;
+                }
+            }
+
+            // A clean exit or a kill (e.g. `mc-cli stop`) is never restarted
+            if !restart_on_crash || stopped_deliberately(status.code()) {
+                break;
+            }
+            if started.elapsed() >= HEALTHY_UPTIME {
+                restarts = 0;
+            }
+            if restarts >= max_restarts {
+                info!("Reached max restarts ({}), giving up.", max_restarts);
+                break;
+            }
+
+            let delay = restart_delay(restarts);
+            restarts += 1;
+            info!(
+                "Server crashed ({}). Restart {}/{} in {}s...",
+                status,
+                restarts,
+                max_restarts,
+                delay.as_secs()
+            );
+            tokio::time::sleep(delay).await;
+        }
+
+        // Remove the PID file when server stops
+        let _ = fs::remove_file(PathBuf::from(PID_FILE));
+        pid_file::remove_info();
+        info!("{} removed", PID_FILE);
     }
 
     Ok(())
 }
+
+/// Refuse to launch when something already holds the game port. Without
+/// server.properties (first run) there is nothing to check yet.
+fn check_port_free() -> Result<(), Box<dyn std::error::Error>> {
+    let Ok(props) = ServerProperties::from_file("server.properties") else {
+        return Ok(());
+    };
+    let (ip, port) = props.bind_address()?;
+    if TcpListener::bind((ip.as_str(), port)).is_err() {
+        return Err(format!(
+            "Port {} already in use (another server running?). Use --force to start anyway.",
+            port
+        )
+        .into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restart_delay_doubles_up_to_cap() {
+        let secs: Vec<u64> = (0..8).map(|a| restart_delay(a).as_secs()).collect();
+        assert_eq!(secs, vec![1, 2, 4, 8, 16, 32, 60, 60]);
+        assert_eq!(restart_delay(200).as_secs(), 60);
+    }
+
+    #[test]
+    fn next_restart_picks_the_soonest_time() {
+        let at = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        let now = chrono::NaiveDate::from_ymd_opt(2024, 3, 1)
+            .unwrap()
+            .and_time(at(12, 0));
+        let times = [at(4, 0), at(18, 30)];
+
+        assert_eq!(
+            next_restart(now, &times),
+            Some(now.date().and_time(at(18, 30)))
+        );
+        // Past today's times, the earliest one tomorrow comes next
+        assert_eq!(
+            next_restart(now, &[at(4, 0), at(12, 0)]),
+            Some(now.date().succ_opt().unwrap().and_time(at(4, 0)))
+        );
+        assert_eq!(next_restart(now, &[]), None);
+    }
+
+    #[test]
+    fn parses_restart_times() {
+        assert_eq!(
+            parse_clock_time("04:30"),
+            Ok(NaiveTime::from_hms_opt(4, 30, 0).unwrap())
+        );
+        assert!(parse_clock_time("24:00").is_err());
+        assert!(parse_clock_time("4pm").is_err());
+        assert_eq!(countdown_label(300), "5 minutes");
+        assert_eq!(countdown_label(10), "10 seconds");
+    }
+
+    #[test]
+    fn signal_exits_are_not_crashes() {
+        assert!(stopped_deliberately(None));
+        assert!(stopped_deliberately(Some(0)));
+        assert!(stopped_deliberately(Some(143)));
+        assert!(!stopped_deliberately(Some(1)));
+    }
+}
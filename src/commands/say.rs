@@ -0,0 +1,112 @@
+use crate::commands::console::render_reply;
+use crate::utils::rcon::send_command;
+use clap::{Arg, Command};
+use tracing::info;
+
+/// Named colors accepted in a text component
+const COLORS: &[&str] = &[
+    "black",
+    "dark_blue",
+    "dark_green",
+    "dark_aqua",
+    "dark_red",
+    "dark_purple",
+    "gold",
+    "gray",
+    "dark_gray",
+    "blue",
+    "green",
+    "aqua",
+    "red",
+    "light_purple",
+    "yellow",
+    "white",
+];
+
+/// Build the say subcommand definition
+pub fn command() -> Command {
+    Command::new("say")
+        .visible_alias("broadcast")
+        .about("Broadcast a chat message to every player via RCON")
+        .arg(
+            Arg::new("message")
+                .help("Message to send; quote it to keep spaces")
+                .required(true)
+                .index(1),
+        )
+        .arg(
+            Arg::new("tellraw")
+                .long("tellraw")
+                .help("Send with tellraw as a JSON text component, without the [Server] prefix")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("color")
+                .long("color")
+                .value_name("COLOR")
+                .help("Text color for --tellraw")
+                .value_parser(COLORS.to_vec())
+                .requires("tellraw"),
+        )
+        .arg(
+            Arg::new("bold")
+                .long("bold")
+                .help("Bold text for --tellraw")
+                .action(clap::ArgAction::SetTrue)
+                .requires("tellraw"),
+        )
+}
+
+/// Execute the say subcommand
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let message = matches.get_one::<String>("message").unwrap();
+    let rcon_cmd = if matches.get_flag("tellraw") {
+        let component = text_component(
+            message,
+            matches.get_one::<String>("color").map(String::as_str),
+            matches.get_flag("bold"),
+        );
+        format!("tellraw @a {}", component)
+    } else {
+        format!("say {}", message)
+    };
+
+    let reply = send_command(&rcon_cmd)
+        .await
+        .map_err(|e| format!("Failed to send message via RCON: {}", e))?;
+    if reply.is_empty() {
+        info!("Message sent.");
+    } else {
+        println!("{}", render_reply(&reply, false));
+    }
+    Ok(())
+}
+
+/// A JSON text component such as `{"text":"Restarting","color":"red"}`
+fn text_component(text: &str, color: Option<&str>, bold: bool) -> serde_json::Value {
+    let mut component = serde_json::json!({ "text": text });
+    if let Some(color) = color {
+        component["color"] = color.into();
+    }
+    if bold {
+        component["bold"] = true.into();
+    }
+    component
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_component() {
+        assert_eq!(
+            text_component("hi \"all\"", None, false).to_string(),
+            r#"{"text":"hi \"all\""}"#
+        );
+        assert_eq!(
+            text_component("Restart in 5m", Some("red"), true),
+            serde_json::json!({ "text": "Restart in 5m", "color": "red", "bold": true })
+        );
+    }
+}
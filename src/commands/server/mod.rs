@@ -0,0 +1,19 @@
+use clap::Command;
+
+pub mod update;
+
+pub fn command() -> Command {
+    Command::new("server")
+        .about("Manage the server software itself")
+        .subcommand(update::command())
+}
+
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    match matches.subcommand() {
+        Some(("update", sub_matches)) => update::execute(sub_matches).await?,
+        _ => {
+            println!("Use a subcommand, e.g., 'server update --help'.");
+        }
+    }
+    Ok(())
+}
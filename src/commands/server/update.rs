@@ -0,0 +1,226 @@
+use crate::commands::init::{
+    download_fabric_server_jar, download_paper_server_jar, fetch_fabric_versions,
+    install_quilt_server, loader_display_name, resolve_paper_version, select_fabric_versions,
+    server_jar,
+};
+use crate::commands::mods::add::latest_compatible;
+use crate::libs::modrinth::{CONCURRENT_LOOKUPS, ModrinthClient};
+use crate::utils::config_file::{McConfig, ProjectKind};
+use crate::utils::pid_file;
+use clap::{Arg, Command};
+use futures::stream::{self, StreamExt};
+use std::fs;
+use std::path::Path;
+use tracing::{info, warn};
+
+pub fn command() -> Command {
+    Command::new("update")
+        .about("Move the server to a newer Minecraft or loader version")
+        .arg(
+            Arg::new("mc_version")
+                .long("mc-version")
+                .value_name("VERSION")
+                .help("Minecraft version to move to (skips the interactive selector)"),
+        )
+        .arg(
+            Arg::new("loader_version")
+                .long("loader-version")
+                .value_name("VERSION")
+                .help("Loader version to move to (skips the interactive selector)"),
+        )
+        .arg(
+            Arg::new("installer_version")
+                .long("installer-version")
+                .value_name("VERSION")
+                .help("Installer version to use (skips the interactive selector)"),
+        )
+        .arg(
+            Arg::new("yes")
+                .long("yes")
+                .short('y')
+                .help("Run without prompting; omitted versions default to latest stable")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("snapshots")
+                .long("snapshots")
+                .help("Include snapshots and pre-releases in the game version selector")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("refresh")
+                .long("refresh")
+                .help("Ignore cached Fabric version lists and fetch fresh ones")
+                .action(clap::ArgAction::SetTrue),
+        )
+}
+
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(pid) = pid_file::running_pid() {
+        return Err(format!(
+            "Server is running (PID {}). Stop it before updating the server jar.",
+            pid
+        )
+        .into());
+    }
+
+    let mut config = McConfig::load()?;
+    let mc_version = matches.get_one::<String>("mc_version");
+    let loader_version = matches.get_one::<String>("loader_version");
+    let installer_version = matches.get_one::<String>("installer_version");
+    let headless = matches.get_flag("yes")
+        || mc_version.is_some()
+        || loader_version.is_some()
+        || installer_version.is_some();
+    let snapshots = matches.get_flag("snapshots");
+    let old_version = config.versions.mc_version.clone();
+    let loader_type = config.versions.loader_type.clone();
+
+    if config.versions.is_modded() {
+        let refresh = matches.get_flag("refresh");
+        let fabric_versions = if headless {
+            fetch_fabric_versions(
+                &loader_type,
+                mc_version,
+                loader_version,
+                installer_version,
+                refresh,
+            )
+            .await?
+        } else {
+            match select_fabric_versions(&loader_type, refresh, snapshots).await? {
+                Some(fv) => fv,
+                None => {
+                    info!("Update cancelled.");
+                    return Ok(());
+                }
+            }
+        };
+        fabric_versions.ensure_resolved()?;
+
+        info!(
+            "Updating to {} Versions:",
+            loader_display_name(&loader_type)
+        );
+        info!("  Loader:    {}", fabric_versions.loader);
+        info!("  Game:      {}", fabric_versions.game);
+        info!("  Installer: {}", fabric_versions.installer);
+
+        backup_jars(&loader_type)?;
+        if loader_type == "quilt" {
+            let java = config.console.java_path.as_deref().unwrap_or("java");
+            install_quilt_server(&fabric_versions, java).await?;
+        } else {
+            download_fabric_server_jar(&fabric_versions).await?;
+        }
+        config.versions.mc_version = fabric_versions.game;
+        config.versions.fabric_version = fabric_versions.loader;
+    } else {
+        if loader_version.is_some() || installer_version.is_some() {
+            return Err("--loader-version and --installer-version do not apply to Paper".into());
+        }
+        let Some((game, build)) = resolve_paper_version(mc_version, headless, snapshots).await?
+        else {
+            info!("Update cancelled.");
+            return Ok(());
+        };
+        info!("Updating to Paper Versions:");
+        info!("  Game:  {}", game);
+        info!("  Build: {}", build.build);
+
+        backup_jars(&loader_type)?;
+        download_paper_server_jar(&game, &build).await?;
+        config.versions.mc_version = game;
+    }
+
+    config.save("mc.toml")?;
+    info!(
+        "Updated server from {} to {}.",
+        old_version, config.versions.mc_version
+    );
+
+    if config.versions.is_modded() && !config.mods.installed.is_empty() {
+        warn_incompatible_mods(&config).await?;
+    }
+    Ok(())
+}
+
+/// Copy the current server jar(s) to `<jar>.bak` so the update can be undone
+fn backup_jars(loader_type: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut jars = vec![server_jar(loader_type)];
+    // Quilt's launcher wraps the vanilla server.jar, which the installer also replaces
+    if !jars.contains(&"server.jar") {
+        jars.push("server.jar");
+    }
+    for jar in jars {
+        if Path::new(jar).exists() {
+            let backup = format!("{}.bak", jar);
+            fs::copy(jar, &backup)?;
+            info!("Backed up {} to {}", jar, backup);
+        }
+    }
+    Ok(())
+}
+
+/// Report installed mods that have no version for the new game version and loader
+async fn warn_incompatible_mods(config: &McConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let client = ModrinthClient::new()?;
+    let mut installed: Vec<&String> = config.mods.installed.keys().collect();
+    installed.sort();
+
+    let results: Vec<(&String, Option<bool>)> = stream::iter(installed)
+        .map(|slug| {
+            let client = &client;
+            async move {
+                let compatible = client
+                    .get_project_versions(slug)
+                    .await
+                    .ok()
+                    .map(|vs| latest_compatible(config, ProjectKind::Mod, &vs).is_some());
+                (slug, compatible)
+            }
+        })
+        .buffered(CONCURRENT_LOOKUPS)
+        .collect()
+        .await;
+
+    let incompatible: Vec<&String> = results
+        .iter()
+        .filter(|(_, c)| *c == Some(false))
+        .map(|(slug, _)| *slug)
+        .collect();
+    let unknown: Vec<&String> = results
+        .iter()
+        .filter(|(_, c)| c.is_none())
+        .map(|(slug, _)| *slug)
+        .collect();
+
+    if !incompatible.is_empty() {
+        warn!(
+            "no {} {} version found for: {}",
+            config.versions.mc_version,
+            config.versions.loader_type,
+            incompatible
+                .iter()
+                .map(|s| s.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    if !unknown.is_empty() {
+        warn!(
+            "could not check compatibility of: {}",
+            unknown
+                .iter()
+                .map(|s| s.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+    if incompatible.is_empty() && unknown.is_empty() {
+        info!("All installed mods have versions for the new server.");
+    } else {
+        info!("Run 'mods update' to move compatible mods to the new version.");
+    }
+    Ok(())
+}
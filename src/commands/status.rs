@@ -1,27 +1,109 @@
+use crate::utils::pid_file::{self, PID_FILE};
+use crate::utils::process::is_alive;
+use crate::utils::rcon::{RconClient, parse_player_list, resolve_rcon_config};
 use clap::Command;
 use std::fs;
-use std::path::Path;
+use std::io::{self, IsTerminal, Write};
+use std::time::Duration;
+use tracing::info;
+
+/// How long to wait on RCON before reporting PID-only status
+const RCON_TIMEOUT: Duration = Duration::from_secs(2);
 
 /// Build the status subcommand definition
 pub fn command() -> Command {
-    Command::new("status").about("Show server running status using mc.lock")
+    Command::new("status").about("Show server running status using the stored PID")
 }
 
 /// Execute the status subcommand
 pub async fn execute(_matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
-    let lock_path = Path::new("mc.lock");
-    if !lock_path.exists() {
-        println!("Server status: stopped (mc.lock not found)");
-        return Ok(());
-    }
+    let lock_path = match pid_file::locate() {
+        Some(p) => p,
+        None => {
+            println!("Server status: stopped ({} not found)", PID_FILE);
+            return Ok(());
+        }
+    };
 
-    let content = fs::read_to_string(lock_path)?;
+    let content = fs::read_to_string(&lock_path)?;
     let pid_str = content.trim();
     if pid_str.is_empty() {
-        println!("Server status: unknown (mc.lock is empty)");
+        println!("Server status: unknown ({} is empty)", lock_path.display());
+        return Ok(());
+    }
+
+    if !is_alive(pid_str) {
+        println!(
+            "Server status: stopped (stale lock, PID {} not running)",
+            pid_str
+        );
+        // Scripts and pipes get a hint instead of a prompt nobody can answer
+        if !io::stdin().is_terminal() {
+            println!(
+                "Run `mc-cli stop` or delete {} to clear it.",
+                lock_path.display()
+            );
+            return Ok(());
+        }
+        print!("Remove stale {}? [y/N] ", lock_path.display());
+        io::stdout().flush()?;
+        let mut input = String::new();
+        let read = io::stdin().read_line(&mut input)?;
+        if read > 0 && matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+            fs::remove_file(&lock_path)?;
+            pid_file::remove_info();
+            info!("{} removed", lock_path.display());
+        }
         return Ok(());
     }
 
     println!("Server status: running (PID {})", pid_str);
+    match pid_file::read_info(pid_str) {
+        Some(info) => println!("Uptime: {}", format_uptime(info.uptime_secs())),
+        None => println!("Uptime: unknown (not started by 'run')"),
+    }
+    // Player info is best-effort; without RCON the PID check above is all we report
+    if let Ok(reply) = list_players().await
+        && let Some(players) = parse_player_list(&reply)
+    {
+        println!("Players: {}/{}", players.online, players.max);
+        if !players.names.is_empty() {
+            println!("Online: {}", players.names.join(", "));
+        }
+    }
     Ok(())
 }
+
+/// Compact duration such as `3h 12m`, `2d 4h` or `45s`
+fn format_uptime(secs: u64) -> String {
+    let (days, hours, mins) = (secs / 86_400, secs / 3_600 % 24, secs / 60 % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, mins)
+    } else if mins > 0 {
+        format!("{}m {}s", mins, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Run `list` over RCON
+async fn list_players() -> Result<String, Box<dyn std::error::Error>> {
+    let (host, port, password) = resolve_rcon_config("server.properties");
+    let mut client = RconClient::connect_with_timeout(&host, port, &password, RCON_TIMEOUT).await?;
+    Ok(client.cmd("list").await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_uptime() {
+        assert_eq!(format_uptime(45), "45s");
+        assert_eq!(format_uptime(125), "2m 5s");
+        assert_eq!(format_uptime(3 * 3_600 + 12 * 60 + 7), "3h 12m");
+        assert_eq!(format_uptime(2 * 86_400 + 4 * 3_600), "2d 4h");
+    }
+}
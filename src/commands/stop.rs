@@ -1,42 +1,140 @@
-use clap::Command;
+use crate::utils::pid_file::{self, PID_FILE};
+use crate::utils::process::{is_alive, kill, terminate};
+use crate::utils::rcon::{RconClient, resolve_rcon_config};
+use clap::{Arg, Command};
 use std::fs;
-use std::path::PathBuf;
-use std::process::Command as SysCommand;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tracing::info;
+
+/// How long to wait for the process to disappear after a kill
+const KILL_WAIT: Duration = Duration::from_secs(5);
 
 /// Build the stop subcommand definition
 pub fn command() -> Command {
-    Command::new("stop").about("Stop the Minecraft server using mc.lock PID")
+    Command::new("stop")
+        .about("Stop the Minecraft server, via RCON if possible, else by the stored PID")
+        .arg(
+            Arg::new("force")
+                .long("force")
+                .short('f')
+                .help("Skip the graceful shutdown and kill the process immediately")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("timeout")
+                .long("timeout")
+                .short('t')
+                .value_name("SECS")
+                .help("Seconds to wait for a graceful shutdown before killing")
+                .value_parser(clap::value_parser!(u64))
+                .default_value("30"),
+        )
 }
 
 /// Execute the stop subcommand
-pub async fn execute(_matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
-    let lock_path = PathBuf::from("mc.lock");
-    if !lock_path.exists() {
-        println!("No mc.lock found. Server may not be running.");
-        return Ok(());
-    }
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let force = matches.get_flag("force");
+    let timeout = Duration::from_secs(*matches.get_one::<u64>("timeout").unwrap());
+
+    let lock_path = match pid_file::locate() {
+        Some(p) => p,
+        None => {
+            info!("No {} found. Server may not be running.", PID_FILE);
+            return Ok(());
+        }
+    };
 
     let pid_str = fs::read_to_string(&lock_path)?.trim().to_string();
     if pid_str.is_empty() {
-        println!("mc.lock is empty. Cannot determine PID.");
-        return Ok(());
+        return Err(format!("{} is empty. Cannot determine PID.", lock_path.display()).into());
+    }
+
+    if !force {
+        match stop_via_rcon().await {
+            Ok(()) => {
+                info!(
+                    "Sent 'stop' via RCON, waiting for PID {} to exit...",
+                    pid_str
+                );
+                if wait_for_exit(&pid_str, timeout).await {
+                    info!("Server stopped gracefully.");
+                    clear_lock(&lock_path);
+                    return Ok(());
+                }
+                info!(
+                    "Server still running after {}s, falling back to kill.",
+                    timeout.as_secs()
+                );
+            }
+            Err(e) => {
+                info!(
+                    "RCON unavailable ({}), sending SIGTERM to PID {}...",
+                    e, pid_str
+                );
+                if terminate(&pid_str)? {
+                    if wait_for_exit(&pid_str, timeout).await {
+                        info!("Server stopped gracefully.");
+                        clear_lock(&lock_path);
+                        return Ok(());
+                    }
+                    info!(
+                        "Server still running after {}s, falling back to kill.",
+                        timeout.as_secs()
+                    );
+                }
+            }
+        }
     }
 
     // Attempt to kill the process
-    let output = SysCommand::new("kill").arg(pid_str.clone()).output()?;
-    if output.status.success() {
-        println!("Sent termination signal to PID {}", pid_str);
-        // Remove lock file
-        let _ = fs::remove_file(&lock_path);
-        println!("mc.lock removed");
+    if kill(&pid_str)? {
+        info!("Sent kill signal to PID {}", pid_str);
     } else {
-        println!(
+        info!(
             "Failed to kill PID {}. It may have already exited.",
             pid_str
         );
-        // Try removing lock anyway if process is gone
-        let _ = fs::remove_file(&lock_path);
     }
+    // Keep the lock while the process is still alive, so status and run see it
+    if !wait_for_exit(&pid_str, KILL_WAIT).await {
+        return Err(format!(
+            "PID {} is still running; {} was left in place.",
+            pid_str,
+            lock_path.display()
+        )
+        .into());
+    }
+    clear_lock(&lock_path);
+
+    Ok(())
+}
+
+/// Remove the PID file and its server info once the process is confirmed gone
+fn clear_lock(lock_path: &Path) {
+    let _ = fs::remove_file(lock_path);
+    pid_file::remove_info();
+    info!("{} removed", lock_path.display());
+}
 
+/// Ask the server to save and shut down through RCON
+async fn stop_via_rcon() -> Result<(), Box<dyn std::error::Error>> {
+    let (host, port, password) = resolve_rcon_config("server.properties");
+    let mut client = RconClient::connect(&host, port, &password).await?;
+    // The server may drop the connection while shutting down, before the reply
+    // arrives; the PID wait that follows is the real success check
+    let _ = client.cmd("stop").await;
     Ok(())
 }
+
+/// Poll until the process exits or the timeout elapses; true if it exited
+async fn wait_for_exit(pid: &str, timeout: Duration) -> bool {
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        if !is_alive(pid) {
+            return true;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    !is_alive(pid)
+}
@@ -0,0 +1,78 @@
+use crate::commands::console::render_reply;
+use crate::utils::rcon::send_command;
+use clap::{Arg, Command};
+
+/// Build the whitelist subcommand definition
+pub fn command() -> Command {
+    let player = || {
+        Arg::new("player")
+            .help("Player name, passed to the server as given")
+            .required(true)
+            .index(1)
+    };
+    Command::new("whitelist")
+        .about("Manage the server whitelist via RCON")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("add")
+                .about("Add a player to the whitelist")
+                .arg(player()),
+        )
+        .subcommand(
+            Command::new("remove")
+                .about("Remove a player from the whitelist")
+                .arg(player()),
+        )
+        .subcommand(Command::new("list").about("List whitelisted players"))
+        .subcommand(Command::new("on").about("Turn on whitelist enforcement"))
+        .subcommand(Command::new("off").about("Turn off whitelist enforcement"))
+}
+
+/// Execute the whitelist subcommand
+pub async fn execute(matches: &clap::ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(rcon_cmd) = rcon_command(matches) else {
+        println!("Use a subcommand, e.g., 'whitelist add --help'.");
+        return Ok(());
+    };
+    let reply = send_command(&rcon_cmd)
+        .await
+        .map_err(|e| format!("Failed to run '{}' via RCON: {}", rcon_cmd, e))?;
+    println!("{}", render_reply(&reply, false));
+    Ok(())
+}
+
+/// The RCON command for the chosen subcommand
+fn rcon_command(matches: &clap::ArgMatches) -> Option<String> {
+    match matches.subcommand()? {
+        (action @ ("add" | "remove"), sub) => Some(format!(
+            "whitelist {} {}",
+            action,
+            sub.get_one::<String>("player").unwrap()
+        )),
+        (action, _) => Some(format!("whitelist {}", action)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rcon_command() {
+        let rcon = |args: &[&str]| {
+            let matches = command().try_get_matches_from(args).unwrap();
+            rcon_command(&matches).unwrap()
+        };
+        assert_eq!(rcon(&["whitelist", "add", "Steve"]), "whitelist add Steve");
+        assert_eq!(
+            rcon(&["whitelist", "remove", "Alex"]),
+            "whitelist remove Alex"
+        );
+        assert_eq!(rcon(&["whitelist", "off"]), "whitelist off");
+        assert!(
+            command()
+                .try_get_matches_from(["whitelist", "add"])
+                .is_err()
+        );
+    }
+}
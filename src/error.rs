@@ -0,0 +1,43 @@
+// Error type shared by the API clients, RCON and config modules
+use crate::utils::config_file::ConfigError;
+use crate::utils::network;
+
+/// Failure kinds callers can match on; commands box these at their boundary
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// No answer was received: --offline, a timeout or a failed connection
+    #[error("{0}")]
+    Network(String),
+    /// A remote API answered with an error status or a body that could not be read
+    #[error("{0}")]
+    Api(String),
+    /// mc.toml or mc.lock could not be parsed or written
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// RCON authentication failed, timed out or sent a malformed packet
+    #[error("{0}")]
+    Rcon(String),
+    /// The requested file, project or version does not exist
+    #[error("{0}")]
+    NotFound(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_decode() {
+            Error::Api(format!("Unexpected response: {}", e))
+        } else {
+            Error::Network(network::describe_error(&e))
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Api(format!("Unexpected response: {}", e))
+    }
+}
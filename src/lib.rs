@@ -0,0 +1,75 @@
+//! Manage Minecraft server projects: the Modrinth, Fabric, Quilt and PaperMC
+//! API clients, mc.toml/mc.lock handling, RCON, and the `mc-cli` command tree.
+use clap::{Arg, Command};
+
+pub mod commands;
+pub mod error;
+pub mod libs;
+pub mod utils;
+
+pub use commands::execute;
+pub use error::{Error, Result};
+
+/// Build the full command tree; also used to generate shell completions
+pub fn cli() -> Command {
+    // Build the CLI with manual subcommand handling for better async support
+    Command::new("mc-cli")
+        .version(env!("CARGO_PKG_VERSION"))
+        .author("BRAVO68WEB")
+        .about("A CLI tool for managing Minecraft projects")
+        .subcommand_required(true)
+        .arg_required_else_help(true)
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .short('C')
+                .value_name("PATH")
+                .help("Project directory, or its mc.toml, to operate on instead of the current one")
+                .global(true),
+        )
+        .arg(
+            Arg::new("quiet")
+                .long("quiet")
+                .short('q')
+                .help("Only print errors and explicitly requested output")
+                .action(clap::ArgAction::SetTrue)
+                .conflicts_with("verbose")
+                .global(true),
+        )
+        .arg(
+            Arg::new("verbose")
+                .long("verbose")
+                .short('v')
+                .help("Print extra detail such as download URLs and launch commands")
+                .action(clap::ArgAction::SetTrue)
+                .global(true),
+        )
+        .arg(
+            Arg::new("offline")
+                .long("offline")
+                .help("Never touch the network; commands that need it fail instead")
+                .action(clap::ArgAction::SetTrue)
+                .global(true),
+        )
+        .subcommand(commands::init::command())
+        .subcommand(commands::run::command())
+        .subcommand(commands::console::command())
+        .subcommand(commands::props::command())
+        .subcommand(commands::status::command())
+        .subcommand(commands::stop::command())
+        .subcommand(commands::whitelist::command())
+        .subcommand(commands::op::command())
+        .subcommand(commands::op::deop_command())
+        .subcommand(commands::say::command())
+        .subcommands(commands::moderation::commands())
+        .subcommand(commands::mods::command())
+        .subcommand(commands::logs::command())
+        .subcommand(commands::config::command())
+        .subcommand(commands::query::command())
+        .subcommand(commands::backup::command())
+        .subcommand(commands::restore::command())
+        .subcommand(commands::server::command())
+        .subcommand(commands::doctor::command())
+        .subcommand(commands::completions::command())
+        .subcommand(commands::man::command())
+}
@@ -1,8 +1,16 @@
+use crate::error::{Error, Result};
+use crate::utils::network;
+use crate::utils::paths;
 use reqwest;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
 
 const BASE_URL: &str = "https://meta.fabricmc.net/v2";
 const USER_AGENT: &str = "BRAVO68WEB/mc-cli/0.1.0";
+const CACHE_TTL: Duration = Duration::from_secs(60 * 60);
 
 // Installer Version Response
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -34,26 +42,94 @@ pub struct GameVersion {
 pub struct FabricClient {
     client: reqwest::Client,
     base_url: String,
+    cache_dir: Option<PathBuf>,
+    cache_ttl: Duration,
+    refresh: bool,
 }
 
 impl FabricClient {
     /// Create a new FabricClient with default settings
-    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
-        let client = reqwest::Client::builder().user_agent(USER_AGENT).build()?;
+    pub fn new() -> Result<Self> {
+        network::require_online("Fabric meta")?;
+        let client = network::http_client(USER_AGENT, network::REQUEST_TIMEOUT)?;
 
         Ok(Self {
             client,
             base_url: BASE_URL.to_string(),
+            cache_dir: paths::cache_dir().map(|d| d.join("fabric")),
+            cache_ttl: CACHE_TTL,
+            refresh: false,
         })
     }
 
     /// Override the base URL (useful for testing)
-    #[allow(dead_code)]
     pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
         self.base_url = base_url.into();
         self
     }
 
+    /// Override the request timeout (default [`network::REQUEST_TIMEOUT`])
+    pub fn with_timeout(mut self, timeout: Duration) -> Result<Self> {
+        self.client = network::http_client(USER_AGENT, timeout)?;
+        Ok(self)
+    }
+
+    /// Override the on-disk cache directory; `None` disables caching
+    pub fn with_cache_dir(mut self, cache_dir: Option<PathBuf>) -> Self {
+        self.cache_dir = cache_dir;
+        self
+    }
+
+    /// Override how long cached responses stay fresh
+    pub fn with_cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    /// Ignore cached responses and always fetch (the cache is still updated)
+    pub fn with_refresh(mut self, refresh: bool) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    /// GET a JSON endpoint, serving it from the on-disk cache while fresh
+    async fn get_cached<T: DeserializeOwned>(&self, endpoint: &str) -> Result<T> {
+        let cache_file = self
+            .cache_dir
+            .as_ref()
+            .map(|d| d.join(cache_key(&self.base_url, endpoint)));
+
+        if let Some(path) = cache_file.as_ref()
+            && !self.refresh
+            && let Ok(modified) = fs::metadata(path).and_then(|m| m.modified())
+            && SystemTime::now()
+                .duration_since(modified)
+                .is_ok_and(|age| age < self.cache_ttl)
+            && let Ok(body) = fs::read_to_string(path)
+            && let Ok(parsed) = serde_json::from_str(&body)
+        {
+            return Ok(parsed);
+        }
+
+        let url = format!("{}/{}", self.base_url, endpoint);
+        let response = network::send(self.client.get(&url)).await?;
+        if !response.status().is_success() {
+            return Err(Error::Api(format!(
+                "API request failed with status: {}",
+                response.status()
+            )));
+        }
+
+        let body = response.text().await?;
+        let parsed = serde_json::from_str(&body)?;
+        if let Some(path) = cache_file.as_ref() {
+            // Caching is best-effort; a read-only home should not break requests
+            let _ = path.parent().map(fs::create_dir_all);
+            let _ = fs::write(path, &body);
+        }
+        Ok(parsed)
+    }
+
     /// Get all available Fabric installer versions
     ///
     /// Returns a list of installer versions sorted by newest first.
@@ -75,18 +151,8 @@ impl FabricClient {
     ///     Ok(())
     /// }
     /// ```
-    pub async fn get_installer_versions(
-        &self,
-    ) -> Result<Vec<InstallerVersion>, Box<dyn std::error::Error>> {
-        let url = format!("{}/versions/installer", self.base_url);
-        let response = self.client.get(&url).send().await?;
-
-        if response.status().is_success() {
-            let versions: Vec<InstallerVersion> = response.json().await?;
-            Ok(versions)
-        } else {
-            Err(format!("API request failed with status: {}", response.status()).into())
-        }
+    pub async fn get_installer_versions(&self) -> Result<Vec<InstallerVersion>> {
+        self.get_cached("versions/installer").await
     }
 
     /// Get all available Fabric loader versions
@@ -113,18 +179,8 @@ impl FabricClient {
     ///     Ok(())
     /// }
     /// ```
-    pub async fn get_loader_versions(
-        &self,
-    ) -> Result<Vec<LoaderVersion>, Box<dyn std::error::Error>> {
-        let url = format!("{}/versions/loader", self.base_url);
-        let response = self.client.get(&url).send().await?;
-
-        if response.status().is_success() {
-            let versions: Vec<LoaderVersion> = response.json().await?;
-            Ok(versions)
-        } else {
-            Err(format!("API request failed with status: {}", response.status()).into())
-        }
+    pub async fn get_loader_versions(&self) -> Result<Vec<LoaderVersion>> {
+        self.get_cached("versions/loader").await
     }
 
     /// Get all available Minecraft game versions
@@ -155,46 +211,198 @@ impl FabricClient {
     ///     Ok(())
     /// }
     /// ```
-    pub async fn get_game_versions(&self) -> Result<Vec<GameVersion>, Box<dyn std::error::Error>> {
-        let url = format!("{}/versions/game", self.base_url);
-        let response = self.client.get(&url).send().await?;
-
-        if response.status().is_success() {
-            let versions: Vec<GameVersion> = response.json().await?;
-            Ok(versions)
-        } else {
-            Err(format!("API request failed with status: {}", response.status()).into())
-        }
+    pub async fn get_game_versions(&self) -> Result<Vec<GameVersion>> {
+        self.get_cached("versions/game").await
     }
 
     /// Get the latest stable installer version
-    #[allow(dead_code)]
-    pub async fn get_latest_installer(
-        &self,
-    ) -> Result<Option<InstallerVersion>, Box<dyn std::error::Error>> {
+    pub async fn get_latest_installer(&self) -> Result<Option<InstallerVersion>> {
         let versions = self.get_installer_versions().await?;
         Ok(versions.into_iter().find(|v| v.stable))
     }
 
     /// Get the latest stable loader version
-    #[allow(dead_code)]
-    pub async fn get_latest_loader(
-        &self,
-    ) -> Result<Option<LoaderVersion>, Box<dyn std::error::Error>> {
+    pub async fn get_latest_loader(&self) -> Result<Option<LoaderVersion>> {
         let versions = self.get_loader_versions().await?;
         Ok(versions.into_iter().find(|v| v.stable))
     }
 
     /// Get the latest stable game version
-    #[allow(dead_code)]
-    pub async fn get_latest_game(&self) -> Result<Option<GameVersion>, Box<dyn std::error::Error>> {
+    pub async fn get_latest_game(&self) -> Result<Option<GameVersion>> {
         let versions = self.get_game_versions().await?;
         Ok(versions.into_iter().find(|v| v.stable))
     }
 }
 
+/// Cache file name for an endpoint, keyed by host so other base URLs never share entries
+fn cache_key(base_url: &str, endpoint: &str) -> String {
+    let host = base_url
+        .split_once("://")
+        .map_or(base_url, |(_, rest)| rest)
+        .split('/')
+        .next()
+        .unwrap_or_default();
+    format!("{}_{}.json", host, endpoint).replace([':', '/'], "_")
+}
+
 impl Default for FabricClient {
     fn default() -> Self {
         Self::new().expect("Failed to create FabricClient")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn serves_fresh_cache_without_network() {
+        let dir = std::env::temp_dir().join(format!(
+            "mc-cli_fabric_cache_test_{}_{}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("127.0.0.1_9_versions_game.json"),
+            r#"[{"version": "1.20.1", "stable": true}]"#,
+        )
+        .unwrap();
+
+        // Unroutable base URL: any network access would fail the test
+        let client = FabricClient::new()
+            .unwrap()
+            .with_base_url("http://127.0.0.1:9")
+            .with_cache_dir(Some(dir.clone()));
+        let games = client.get_game_versions().await.unwrap();
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].version, "1.20.1");
+
+        // Refresh bypasses the cache and hits the (unreachable) network
+        let client = client.with_refresh(true);
+        assert!(client.get_game_versions().await.is_err());
+
+        // Another host must not be served this host's entry
+        let other = FabricClient::new()
+            .unwrap()
+            .with_base_url("http://127.0.0.2:9")
+            .with_cache_dir(Some(dir.clone()));
+        assert!(other.get_game_versions().await.is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn latest_is_none_without_stable_versions() {
+        let dir = std::env::temp_dir().join(format!(
+            "mc-cli_fabric_snapshot_test_{}_{}",
+            std::process::id(),
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("127.0.0.1_9_versions_game.json"),
+            r#"[{"version": "24w14a", "stable": false}, {"version": "24w13a", "stable": false}]"#,
+        )
+        .unwrap();
+
+        let client = FabricClient::new()
+            .unwrap()
+            .with_base_url("http://127.0.0.1:9")
+            .with_cache_dir(Some(dir.clone()));
+        assert!(client.get_latest_game().await.unwrap().is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn parses_meta_responses() {
+        let base = crate::utils::mock_http::serve(vec![
+            (
+                "/v2/versions/game",
+                "200 OK",
+                r#"[{"version": "24w14a", "stable": false}, {"version": "1.20.4", "stable": true}]"#,
+            ),
+            (
+                "/v2/versions/loader",
+                "200 OK",
+                r#"[{"separator": ".", "build": 2, "maven": "net.fabricmc:fabric-loader:0.16.0",
+                     "version": "0.16.0", "stable": true}]"#,
+            ),
+            (
+                "/v2/versions/installer",
+                "200 OK",
+                r#"[{"url": "https://maven.fabricmc.net/net/fabricmc/fabric-installer/1.0.1/fabric-installer-1.0.1.jar",
+                     "maven": "net.fabricmc:fabric-installer:1.0.1", "version": "1.0.1", "stable": true}]"#,
+            ),
+        ])
+        .await;
+        let client = FabricClient::new()
+            .unwrap()
+            .with_base_url(format!("{}/v2", base))
+            .with_cache_dir(None);
+
+        let game = client.get_latest_game().await.unwrap().unwrap();
+        assert_eq!(game.version, "1.20.4");
+        let loader = client.get_latest_loader().await.unwrap().unwrap();
+        assert_eq!((loader.version.as_str(), loader.build), ("0.16.0", 2));
+        let installer = client.get_latest_installer().await.unwrap().unwrap();
+        assert_eq!(installer.maven, "net.fabricmc:fabric-installer:1.0.1");
+    }
+
+    #[tokio::test]
+    async fn error_status_and_bad_json_are_api_errors() {
+        let base = crate::utils::mock_http::serve(vec![(
+            "/v2/versions/game",
+            "200 OK",
+            r#"{"unexpected": "shape"}"#,
+        )])
+        .await;
+        let client = FabricClient::new()
+            .unwrap()
+            .with_base_url(format!("{}/v2", base))
+            .with_cache_dir(None);
+
+        let err = client.get_game_versions().await.unwrap_err();
+        assert!(matches!(err, Error::Api(_)), "{}", err);
+        let err = client.get_loader_versions().await.unwrap_err();
+        assert!(matches!(err, Error::Api(_)), "{}", err);
+        assert!(err.to_string().contains("404"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn stalled_server_times_out() {
+        // Accept connections but never answer
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let client = FabricClient::new()
+            .unwrap()
+            .with_base_url(format!("http://{}/v2", addr))
+            .with_cache_dir(None)
+            .with_timeout(Duration::from_millis(50))
+            .unwrap();
+        let err = client.get_game_versions().await.unwrap_err();
+        assert!(matches!(err, Error::Network(_)));
+        assert!(err.to_string().contains("timed out"), "{}", err);
+        drop(listener);
+    }
+
+    #[test]
+    fn cache_key_includes_host() {
+        assert_eq!(
+            cache_key(BASE_URL, "versions/game"),
+            "meta.fabricmc.net_versions_game.json"
+        );
+        assert_eq!(
+            cache_key("http://localhost:8080/v2", "versions/loader"),
+            "localhost_8080_versions_loader.json"
+        );
+    }
+}
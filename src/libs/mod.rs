@@ -1,2 +1,4 @@
 pub mod fabric;
 pub mod modrinth;
+pub mod paper;
+pub mod quilt;
@@ -1,8 +1,17 @@
+use crate::error::{Error, Result};
+use crate::utils::global_config::GlobalConfig;
+use crate::utils::network;
 use reqwest;
 use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
 const BASE_URL: &str = "https://api.modrinth.com/v2";
 const USER_AGENT: &str = "BRAVO68WEB/mc-cli/0.1.0";
+const TOKEN_ENV: &str = "MODRINTH_TOKEN";
+
+/// Maximum number of Modrinth lookups callers should keep in flight at once
+pub const CONCURRENT_LOOKUPS: usize = 5;
 
 // Search Results Response
 #[derive(Debug, Deserialize, Serialize)]
@@ -63,7 +72,6 @@ pub struct SearchQuery {
     pub filters: Option<String>,
 }
 
-#[allow(dead_code)]
 impl SearchQuery {
     pub fn new() -> Self {
         Self::default()
@@ -107,29 +115,106 @@ pub struct ApiError {
     pub description: String,
 }
 
-// Main API Client
+/// Main API Client
+///
+/// If the `MODRINTH_TOKEN` environment variable is set when the client is
+/// created, its value is sent as the `Authorization` header on every request,
+/// which raises the rate limit and allows access to authenticated endpoints.
+/// Without it, `modrinth_token` from the global config is used.
+/// Use [`ModrinthClient::with_token`] to set or clear the token explicitly.
 pub struct ModrinthClient {
     client: reqwest::Client,
     base_url: String,
+    token: Option<String>,
+    /// Earliest time the next request may be sent, set when the rate limit is exhausted
+    throttle: Mutex<Option<Instant>>,
 }
 
 impl ModrinthClient {
-    #[allow(dead_code)]
-    pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
-        let client = reqwest::Client::builder().user_agent(USER_AGENT).build()?;
+    pub fn new() -> Result<Self> {
+        network::require_online("Modrinth")?;
+        let client = network::http_client(USER_AGENT, network::REQUEST_TIMEOUT)?;
 
         Ok(Self {
             client,
             base_url: BASE_URL.to_string(),
+            token: std::env::var(TOKEN_ENV)
+                .ok()
+                .or_else(|| GlobalConfig::load().ok()?.modrinth_token)
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty()),
+            throttle: Mutex::new(None),
         })
     }
 
-    #[allow(dead_code)]
     pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
         self.base_url = base_url.into();
         self
     }
 
+    /// Override the request timeout (default [`network::REQUEST_TIMEOUT`])
+    pub fn with_timeout(mut self, timeout: Duration) -> Result<Self> {
+        self.client = network::http_client(USER_AGENT, timeout)?;
+        Ok(self)
+    }
+
+    /// Override the API token sent in the `Authorization` header
+    pub fn with_token(mut self, token: Option<String>) -> Self {
+        self.token = token;
+        self
+    }
+
+    /// Send a request, honoring Modrinth's rate limit headers. On HTTP 429 the
+    /// request is retried once after the advertised reset.
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let retry = request.try_clone();
+        let response = self.send_throttled(request).await?;
+        if response.status() != reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Ok(response);
+        }
+
+        let wait = rate_limit_reset(&response).unwrap_or(Duration::from_secs(1));
+        let retry = match retry {
+            Some(r) => r,
+            None => return Ok(response),
+        };
+        tokio::time::sleep(wait).await;
+
+        let response = self.send_throttled(retry).await?;
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let wait = rate_limit_reset(&response).unwrap_or(wait);
+            return Err(Error::Api(format!(
+                "Modrinth rate limit exceeded; try again in {}s",
+                wait.as_secs().max(1)
+            )));
+        }
+        Ok(response)
+    }
+
+    /// Wait out any exhausted rate limit window, then send
+    async fn send_throttled(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let wait = self
+            .throttle
+            .lock()
+            .unwrap()
+            .and_then(|until| until.checked_duration_since(Instant::now()));
+        if let Some(wait) = wait {
+            tokio::time::sleep(wait).await;
+        }
+
+        let request = match self.token.as_deref() {
+            Some(token) => request.header(reqwest::header::AUTHORIZATION, token),
+            None => request,
+        };
+        let response = network::send(request).await?;
+        if header_u64(&response, "x-ratelimit-remaining") == Some(0)
+            && let Some(reset) = rate_limit_reset(&response)
+        {
+            *self.throttle.lock().unwrap() = Some(Instant::now() + reset);
+        }
+        Ok(response)
+    }
+
     /// Search for projects on Modrinth
     ///
     /// # Arguments
@@ -162,11 +247,7 @@ impl ModrinthClient {
     ///     Ok(())
     /// }
     /// ```
-    #[allow(dead_code)]
-    pub async fn search_projects(
-        &self,
-        query: Option<SearchQuery>,
-    ) -> Result<SearchResults, Box<dyn std::error::Error>> {
+    pub async fn search_projects(&self, query: Option<SearchQuery>) -> Result<SearchResults> {
         let url = format!("{}/search", self.base_url);
 
         let mut request = self.client.get(&url);
@@ -175,66 +256,119 @@ impl ModrinthClient {
             request = request.query(&q);
         }
 
-        let response = request.send().await?;
+        let response = self.send(request).await?;
 
         if response.status().is_success() {
             let results: SearchResults = response.json().await?;
             Ok(results)
         } else {
-            let error: ApiError = response.json().await?;
-            Err(format!("{}: {}", error.error, error.description).into())
+            Err(api_error(response).await)
         }
     }
 
     /// Get a project by ID or slug
-    #[allow(dead_code)]
-    pub async fn get_project(
-        &self,
-        id_or_slug: &str,
-    ) -> Result<Project, Box<dyn std::error::Error>> {
+    pub async fn get_project(&self, id_or_slug: &str) -> Result<Project> {
         let url = format!("{}/project/{}", self.base_url, id_or_slug);
-        let response = self.client.get(&url).send().await?;
+        let response = self.send(self.client.get(&url)).await?;
         if response.status().is_success() {
             let project: Project = response.json().await?;
             Ok(project)
         } else {
-            let error: ApiError = response.json().await?;
-            Err(format!("{}: {}", error.error, error.description).into())
+            Err(api_error(response).await)
+        }
+    }
+
+    /// Get a project by ID or slug, or `None` if Modrinth has no such project
+    pub async fn find_project(&self, id_or_slug: &str) -> Result<Option<Project>> {
+        let url = format!("{}/project/{}", self.base_url, id_or_slug);
+        let response = self.send(self.client.get(&url)).await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            Ok(None)
+        } else if response.status().is_success() {
+            let project: Project = response.json().await?;
+            Ok(Some(project))
+        } else {
+            Err(api_error(response).await)
         }
     }
 
     /// List versions for a project by ID or slug
-    #[allow(dead_code)]
-    pub async fn get_project_versions(
-        &self,
-        id_or_slug: &str,
-    ) -> Result<Vec<Version>, Box<dyn std::error::Error>> {
+    pub async fn get_project_versions(&self, id_or_slug: &str) -> Result<Vec<Version>> {
         let url = format!("{}/project/{}/version", self.base_url, id_or_slug);
-        let response = self.client.get(&url).send().await?;
+        let response = self.send(self.client.get(&url)).await?;
         if response.status().is_success() {
             let versions: Vec<Version> = response.json().await?;
             Ok(versions)
         } else {
-            let error: ApiError = response.json().await?;
-            Err(format!("{}: {}", error.error, error.description).into())
+            Err(api_error(response).await)
         }
     }
 
     /// Get a version by ID
-    #[allow(dead_code)]
-    pub async fn get_version(&self, id: &str) -> Result<Version, Box<dyn std::error::Error>> {
+    pub async fn get_version(&self, id: &str) -> Result<Version> {
         let url = format!("{}/version/{}", self.base_url, id);
-        let response = self.client.get(&url).send().await?;
+        let response = self.send(self.client.get(&url)).await?;
         if response.status().is_success() {
             let version: Version = response.json().await?;
             Ok(version)
         } else {
-            let error: ApiError = response.json().await?;
-            Err(format!("{}: {}", error.error, error.description).into())
+            Err(api_error(response).await)
         }
     }
 }
 
+/// Longest slice of a non-JSON error body quoted back to the user
+const ERROR_SNIPPET_CHARS: usize = 200;
+
+/// Describe a failed response, using Modrinth's error JSON when the body has it.
+/// A 404 is reported as [`Error::NotFound`].
+async fn api_error(response: reqwest::Response) -> Error {
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    let message = describe_error(status, &body);
+    if status == reqwest::StatusCode::NOT_FOUND {
+        Error::NotFound(message)
+    } else {
+        Error::Api(message)
+    }
+}
+
+/// `error: description` for an `ApiError` body; otherwise the status and a
+/// whitespace-collapsed snippet, since proxies answer with HTML or plain text
+fn describe_error(status: reqwest::StatusCode, body: &str) -> String {
+    if let Ok(error) = serde_json::from_str::<ApiError>(body) {
+        return format!("{}: {}", error.error, error.description);
+    }
+    let text = body.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return format!("Modrinth returned {}", status);
+    }
+    let snippet: String = text.chars().take(ERROR_SNIPPET_CHARS).collect();
+    let ellipsis = if snippet.len() < text.len() {
+        "..."
+    } else {
+        ""
+    };
+    format!("Modrinth returned {}: {}{}", status, snippet, ellipsis)
+}
+
+/// Parse a numeric response header
+fn header_u64(response: &reqwest::Response, name: &str) -> Option<u64> {
+    response
+        .headers()
+        .get(name)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Time until the rate limit window resets, from `X-Ratelimit-Reset` (seconds)
+fn rate_limit_reset(response: &reqwest::Response) -> Option<Duration> {
+    header_u64(response, "x-ratelimit-reset").map(Duration::from_secs)
+}
+
 impl Default for ModrinthClient {
     fn default() -> Self {
         Self::new().expect("Failed to create ModrinthClient")
@@ -258,17 +392,33 @@ pub struct Project {
 }
 
 // Version response (subset)
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Version {
     pub id: String,
+    pub project_id: String,
     pub name: Option<String>,
     pub version_number: Option<String>,
     pub game_versions: Vec<String>,
     pub loaders: Vec<String>,
     pub files: Vec<VersionFile>,
+    #[serde(default)]
+    pub dependencies: Vec<Dependency>,
+    /// RFC 3339 publish time, e.g. 2023-06-12T17:33:02.123456Z
+    #[serde(default)]
+    pub date_published: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+// Version dependency; either id may be absent depending on how it was declared
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Dependency {
+    pub version_id: Option<String>,
+    pub project_id: Option<String>,
+    pub file_name: Option<String>,
+    /// One of "required", "optional", "incompatible", "embedded"
+    pub dependency_type: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct VersionFile {
     pub url: String,
     pub filename: String,
@@ -276,8 +426,228 @@ pub struct VersionFile {
     pub primary: Option<bool>,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Hashes {
     pub sha1: Option<String>,
     pub sha512: Option<String>,
 }
+
+/// Web page of a project on modrinth.com
+pub fn project_url(slug: &str) -> String {
+    format!("https://modrinth.com/project/{}", slug)
+}
+
+/// Find a version by Modrinth version id or by version_number. An exact id match
+/// wins, since a version_number can collide with another version's id.
+pub fn find_version<'a>(versions: &'a [Version], wanted: &str) -> Option<&'a Version> {
+    versions.iter().find(|v| v.id == wanted).or_else(|| {
+        versions
+            .iter()
+            .find(|v| v.version_number.as_deref() == Some(wanted))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::StatusCode;
+
+    #[test]
+    fn test_describe_error_falls_back_to_body_text() {
+        assert_eq!(
+            describe_error(
+                StatusCode::BAD_REQUEST,
+                r#"{"error": "invalid_input", "description": "bad facets"}"#
+            ),
+            "invalid_input: bad facets"
+        );
+        assert_eq!(
+            describe_error(
+                StatusCode::BAD_GATEWAY,
+                "<html>\n  <body>Bad gateway</body>\n</html>"
+            ),
+            "Modrinth returned 502 Bad Gateway: <html> <body>Bad gateway</body> </html>"
+        );
+        assert_eq!(
+            describe_error(StatusCode::SERVICE_UNAVAILABLE, ""),
+            "Modrinth returned 503 Service Unavailable"
+        );
+        let long = "x".repeat(ERROR_SNIPPET_CHARS + 10);
+        assert!(describe_error(StatusCode::BAD_GATEWAY, &long).ends_with("x..."));
+    }
+
+    const SEARCH: &str = r#"{
+        "hits": [{
+            "slug": "lithium",
+            "title": "Lithium",
+            "description": "No-compromises game logic optimization mod",
+            "categories": ["fabric", "optimization"],
+            "client_side": "optional",
+            "server_side": "optional",
+            "project_type": "mod",
+            "downloads": 28000000,
+            "icon_url": "https://cdn.modrinth.com/data/gvQqBUqZ/icon.png",
+            "color": null,
+            "thread_id": "gvQqBUqZ",
+            "monetization_status": "monetized",
+            "project_id": "gvQqBUqZ",
+            "author": "jellysquid3",
+            "display_categories": ["optimization"],
+            "versions": ["1.20.1", "1.21.1"],
+            "follows": 15000,
+            "date_created": "2021-01-03T00:53:34.185936Z",
+            "date_modified": "2024-08-13T22:34:01.245452Z",
+            "latest_version": null,
+            "license": "LGPL-3.0-only",
+            "gallery": [],
+            "featured_gallery": null
+        }],
+        "offset": 0,
+        "limit": 10,
+        "total_hits": 1
+    }"#;
+
+    const PROJECT: &str = r#"{
+        "id": "gvQqBUqZ",
+        "slug": "lithium",
+        "project_type": "mod",
+        "team": "peSx5UYg",
+        "title": "Lithium",
+        "description": "No-compromises game logic optimization mod",
+        "categories": ["optimization"],
+        "downloads": 28000000,
+        "client_side": "optional",
+        "server_side": "optional",
+        "versions": ["ZSNsJrPI", "nMhjKWVE"]
+    }"#;
+
+    const VERSIONS: &str = r#"[
+        {
+            "id": "ZSNsJrPI",
+            "project_id": "gvQqBUqZ",
+            "name": "Lithium 0.11.2",
+            "version_number": "mc1.20.1-0.11.2",
+            "game_versions": ["1.20.1"],
+            "loaders": ["fabric", "quilt"],
+            "files": [{
+                "url": "https://cdn.modrinth.com/data/gvQqBUqZ/versions/ZSNsJrPI/lithium-fabric-mc1.20.1-0.11.2.jar",
+                "filename": "lithium-fabric-mc1.20.1-0.11.2.jar",
+                "hashes": {"sha1": "aa", "sha512": "bb"},
+                "primary": true,
+                "size": 664812
+            }],
+            "dependencies": [
+                {"version_id": null, "project_id": "P7dR8mSH", "file_name": null, "dependency_type": "required"}
+            ]
+        },
+        {
+            "id": "nMhjKWVE",
+            "project_id": "gvQqBUqZ",
+            "name": null,
+            "version_number": "mc1.20.1-0.11.1",
+            "game_versions": ["1.20.1"],
+            "loaders": ["fabric"],
+            "files": [{
+                "url": "https://cdn.modrinth.com/lithium-0.11.1.jar",
+                "filename": "lithium-0.11.1.jar",
+                "hashes": {"sha512": "cc"}
+            }]
+        }
+    ]"#;
+
+    async fn mock_client(
+        routes: Vec<(&'static str, &'static str, &'static str)>,
+    ) -> ModrinthClient {
+        let base = crate::utils::mock_http::serve(routes).await;
+        ModrinthClient::new()
+            .unwrap()
+            .with_base_url(format!("{}/v2", base))
+            .with_token(None)
+    }
+
+    #[tokio::test]
+    async fn test_parses_search_and_project_responses() {
+        let client = mock_client(vec![
+            ("/v2/search", "200 OK", SEARCH),
+            ("/v2/project/lithium", "200 OK", PROJECT),
+        ])
+        .await;
+
+        let results = client
+            .search_projects(Some(SearchQuery::new().query("lithium")))
+            .await
+            .unwrap();
+        assert_eq!(results.total_hits, 1);
+        let hit = &results.hits[0];
+        assert_eq!(hit.slug, "lithium");
+        assert_eq!(hit.color, None);
+        assert_eq!(hit.latest_version, None);
+        assert_eq!(
+            hit.icon_url.as_deref(),
+            Some("https://cdn.modrinth.com/data/gvQqBUqZ/icon.png")
+        );
+
+        let project = client.get_project("lithium").await.unwrap();
+        assert_eq!(project.id, "gvQqBUqZ");
+        // `author` is absent from project responses
+        assert_eq!(project.author, None);
+        assert_eq!(project.server_side.as_deref(), Some("optional"));
+        assert_eq!(project.versions.unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_parses_versions_with_optional_fields() {
+        let client = mock_client(vec![("/v2/project/lithium/version", "200 OK", VERSIONS)]).await;
+
+        let versions = client.get_project_versions("lithium").await.unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[0].files[0].primary, Some(true));
+        assert_eq!(
+            versions[0].dependencies[0].project_id.as_deref(),
+            Some("P7dR8mSH")
+        );
+        assert_eq!(versions[0].dependencies[0].version_id, None);
+        assert_eq!(versions[1].name, None);
+        assert!(versions[1].dependencies.is_empty());
+        assert_eq!(versions[1].files[0].primary, None);
+        assert_eq!(versions[1].files[0].hashes.sha1, None);
+    }
+
+    #[tokio::test]
+    async fn test_error_responses() {
+        let client = mock_client(vec![(
+            "/v2/search",
+            "400 Bad Request",
+            r#"{"error": "invalid_input", "description": "Error while parsing facets"}"#,
+        )])
+        .await;
+
+        let err = client.search_projects(None).await.unwrap_err();
+        assert!(matches!(err, Error::Api(_)));
+        assert_eq!(err.to_string(), "invalid_input: Error while parsing facets");
+
+        let err = client.get_project("missing").await.unwrap_err();
+        assert!(matches!(err, Error::NotFound(_)));
+        assert!(client.find_project("missing").await.unwrap().is_none());
+    }
+
+    #[test]
+    fn test_find_version_prefers_exact_id() {
+        let version = |id: &str, number: &str| Version {
+            id: id.to_string(),
+            project_id: "AANobbMI".to_string(),
+            name: None,
+            version_number: Some(number.to_string()),
+            game_versions: vec![],
+            loaders: vec![],
+            files: vec![],
+            dependencies: vec![],
+            date_published: None,
+        };
+        let versions = vec![version("IZskON6d", "0.5.3"), version("0.5.3", "0.5.2")];
+        assert_eq!(find_version(&versions, "IZskON6d").unwrap().id, "IZskON6d");
+        assert_eq!(find_version(&versions, "0.5.3").unwrap().id, "0.5.3");
+        assert_eq!(find_version(&versions, "0.5.2").unwrap().id, "0.5.3");
+        assert!(find_version(&versions, "0.4.0").is_none());
+    }
+}
@@ -0,0 +1,202 @@
+use crate::error::{Error, Result};
+use crate::utils::network;
+use reqwest;
+use serde::{Deserialize, Serialize};
+
+const BASE_URL: &str = "https://api.papermc.io/v2";
+const USER_AGENT: &str = "BRAVO68WEB/mc-cli/0.1.0";
+const PROJECT: &str = "paper";
+
+// Project Response
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ProjectInfo {
+    pub project_id: String,
+    /// Supported Minecraft versions, oldest first
+    pub versions: Vec<String>,
+}
+
+// Builds Response
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BuildList {
+    pub version: String,
+    /// Builds for the version, oldest first
+    pub builds: Vec<Build>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Build {
+    pub build: u32,
+    /// "default" for stable builds, "experimental" otherwise
+    pub channel: String,
+    pub downloads: Downloads,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Downloads {
+    pub application: Download,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Download {
+    pub name: String,
+    pub sha256: String,
+}
+
+impl BuildList {
+    /// Builds reordered newest first
+    pub fn newest_first(self) -> Vec<Build> {
+        self.builds.into_iter().rev().collect()
+    }
+}
+
+impl Build {
+    /// Whether PaperMC considers this build stable
+    pub fn stable(&self) -> bool {
+        self.channel == "default"
+    }
+}
+
+// Main PaperMC Downloads API Client
+pub struct PaperClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl PaperClient {
+    /// Create a new PaperClient with default settings
+    pub fn new() -> Result<Self> {
+        network::require_online("The PaperMC API")?;
+        let client = network::http_client(USER_AGENT, network::REQUEST_TIMEOUT)?;
+
+        Ok(Self {
+            client,
+            base_url: BASE_URL.to_string(),
+        })
+    }
+
+    /// Override the base URL (useful for testing)
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Get all Minecraft versions Paper supports, newest first
+    pub async fn get_versions(&self) -> Result<Vec<String>> {
+        let url = format!("{}/projects/{}", self.base_url, PROJECT);
+        let response = network::send(self.client.get(&url)).await?;
+
+        if response.status().is_success() {
+            let project: ProjectInfo = response.json().await?;
+            Ok(project.versions.into_iter().rev().collect())
+        } else {
+            Err(Error::Api(format!(
+                "API request failed with status: {}",
+                response.status()
+            )))
+        }
+    }
+
+    /// Get the latest release Minecraft version (skipping pre-releases like `1.21-pre1`)
+    pub async fn get_latest_version(&self) -> Result<Option<String>> {
+        let versions = self.get_versions().await?;
+        Ok(versions.into_iter().find(|v| !v.contains('-')))
+    }
+
+    /// Get all builds for a Minecraft version, newest first
+    pub async fn get_builds(&self, mc_version: &str) -> Result<Vec<Build>> {
+        let url = format!(
+            "{}/projects/{}/versions/{}/builds",
+            self.base_url, PROJECT, mc_version
+        );
+        let response = network::send(self.client.get(&url)).await?;
+
+        if response.status().is_success() {
+            let list: BuildList = response.json().await?;
+            Ok(list.newest_first())
+        } else if response.status() == reqwest::StatusCode::NOT_FOUND {
+            Err(Error::NotFound(format!(
+                "Paper does not support Minecraft {}",
+                mc_version
+            )))
+        } else {
+            Err(Error::Api(format!(
+                "API request failed with status: {}",
+                response.status()
+            )))
+        }
+    }
+
+    /// Get the latest stable build for a Minecraft version
+    pub async fn get_latest_build(&self, mc_version: &str) -> Result<Option<Build>> {
+        let builds = self.get_builds(mc_version).await?;
+        Ok(builds.into_iter().find(|b| b.stable()))
+    }
+
+    /// URL of a build's server jar
+    pub fn download_url(&self, mc_version: &str, build: &Build) -> String {
+        format!(
+            "{}/projects/{}/versions/{}/builds/{}/downloads/{}",
+            self.base_url, PROJECT, mc_version, build.build, build.downloads.application.name
+        )
+    }
+}
+
+impl Default for PaperClient {
+    fn default() -> Self {
+        Self::new().expect("Failed to create PaperClient")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latest_stable_build_skips_experimental() {
+        let list: BuildList = serde_json::from_str(
+            r#"{
+                "project_id": "paper",
+                "version": "1.21.1",
+                "builds": [
+                    {"build": 10, "channel": "default", "downloads": {"application": {"name": "paper-1.21.1-10.jar", "sha256": "aa"}}},
+                    {"build": 11, "channel": "default", "downloads": {"application": {"name": "paper-1.21.1-11.jar", "sha256": "bb"}}},
+                    {"build": 12, "channel": "experimental", "downloads": {"application": {"name": "paper-1.21.1-12.jar", "sha256": "cc"}}}
+                ]
+            }"#,
+        )
+        .unwrap();
+        let latest = list
+            .newest_first()
+            .into_iter()
+            .find(|b| b.stable())
+            .unwrap();
+        assert_eq!(latest.build, 11);
+
+        let client = PaperClient::new()
+            .unwrap()
+            .with_base_url("http://paper.test/v2");
+        assert_eq!(
+            client.download_url("1.21.1", &latest),
+            "http://paper.test/v2/projects/paper/versions/1.21.1/builds/11/downloads/paper-1.21.1-11.jar"
+        );
+    }
+
+    #[tokio::test]
+    async fn error_statuses_map_to_crate_errors() {
+        let base = crate::utils::mock_http::serve(vec![(
+            "/v2/projects/paper",
+            "500 Internal Server Error",
+            "",
+        )])
+        .await;
+        let client = PaperClient::new()
+            .unwrap()
+            .with_base_url(format!("{}/v2", base));
+
+        let err = client.get_builds("1.2.5").await.unwrap_err();
+        assert!(matches!(err, Error::NotFound(_)), "{}", err);
+        assert!(err.to_string().contains("1.2.5"), "{}", err);
+        let err = client.get_versions().await.unwrap_err();
+        assert!(matches!(err, Error::Api(_)), "{}", err);
+    }
+}
@@ -0,0 +1,135 @@
+use crate::error::{Error, Result};
+use crate::utils::network;
+use reqwest;
+use serde::{Deserialize, Serialize};
+
+const BASE_URL: &str = "https://meta.quiltmc.org/v3";
+const USER_AGENT: &str = "BRAVO68WEB/mc-cli/0.1.0";
+
+// Installer Version Response
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct InstallerVersion {
+    pub url: String,
+    pub maven: String,
+    pub version: String,
+}
+
+// Loader Version Response
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct LoaderVersion {
+    pub separator: String,
+    pub build: u32,
+    pub maven: String,
+    pub version: String,
+}
+
+impl LoaderVersion {
+    /// Quilt meta has no stable flag; pre-releases carry a suffix like `-beta.1`
+    pub fn stable(&self) -> bool {
+        !self.version.contains('-')
+    }
+}
+
+// Game Version Response
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct GameVersion {
+    pub version: String,
+    pub stable: bool,
+}
+
+// Main Quilt Meta API Client
+pub struct QuiltClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl QuiltClient {
+    /// Create a new QuiltClient with default settings
+    pub fn new() -> Result<Self> {
+        network::require_online("Quilt meta")?;
+        let client = network::http_client(USER_AGENT, network::REQUEST_TIMEOUT)?;
+
+        Ok(Self {
+            client,
+            base_url: BASE_URL.to_string(),
+        })
+    }
+
+    /// Override the base URL (useful for testing)
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Get all available Quilt installer versions, newest first
+    pub async fn get_installer_versions(&self) -> Result<Vec<InstallerVersion>> {
+        let url = format!("{}/versions/installer", self.base_url);
+        let response = network::send(self.client.get(&url)).await?;
+
+        if response.status().is_success() {
+            let versions: Vec<InstallerVersion> = response.json().await?;
+            Ok(versions)
+        } else {
+            Err(Error::Api(format!(
+                "API request failed with status: {}",
+                response.status()
+            )))
+        }
+    }
+
+    /// Get all available Quilt loader versions, newest first
+    pub async fn get_loader_versions(&self) -> Result<Vec<LoaderVersion>> {
+        let url = format!("{}/versions/loader", self.base_url);
+        let response = network::send(self.client.get(&url)).await?;
+
+        if response.status().is_success() {
+            let versions: Vec<LoaderVersion> = response.json().await?;
+            Ok(versions)
+        } else {
+            Err(Error::Api(format!(
+                "API request failed with status: {}",
+                response.status()
+            )))
+        }
+    }
+
+    /// Get all Minecraft game versions known to Quilt, including snapshots
+    pub async fn get_game_versions(&self) -> Result<Vec<GameVersion>> {
+        let url = format!("{}/versions/game", self.base_url);
+        let response = network::send(self.client.get(&url)).await?;
+
+        if response.status().is_success() {
+            let versions: Vec<GameVersion> = response.json().await?;
+            Ok(versions)
+        } else {
+            Err(Error::Api(format!(
+                "API request failed with status: {}",
+                response.status()
+            )))
+        }
+    }
+
+    /// Get the latest installer version
+    pub async fn get_latest_installer(&self) -> Result<Option<InstallerVersion>> {
+        let versions = self.get_installer_versions().await?;
+        Ok(versions.into_iter().next())
+    }
+
+    /// Get the latest stable loader version
+    pub async fn get_latest_loader(&self) -> Result<Option<LoaderVersion>> {
+        let versions = self.get_loader_versions().await?;
+        Ok(versions.into_iter().find(|v| v.stable()))
+    }
+
+    /// Get the latest stable game version
+    pub async fn get_latest_game(&self) -> Result<Option<GameVersion>> {
+        let versions = self.get_game_versions().await?;
+        Ok(versions.into_iter().find(|v| v.stable))
+    }
+}
+
+impl Default for QuiltClient {
+    fn default() -> Self {
+        Self::new().expect("Failed to create QuiltClient")
+    }
+}
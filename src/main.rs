@@ -1,43 +1,41 @@
-use clap::{Command, Parser};
-
-mod commands;
-mod libs;
-mod utils;
-
-/// Minecraft CLI - A tool for managing Minecraft projects
-#[derive(Parser, Debug)]
-#[command(name = "mc-cli")]
-#[command(version, about = "A CLI tool for managing Minecraft projects", long_about = None)]
-struct Cli {
-    #[command(subcommand)]
-    command: Option<Commands>,
-}
-
-#[derive(Parser, Debug)]
-enum Commands {
-    // We'll handle subcommands manually for more control
-}
+use mc_cli::{cli, commands, utils};
+use std::path::Path;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Build the CLI with manual subcommand handling for better async support
-    let matches = Command::new("mc-cli")
-        .version(env!("CARGO_PKG_VERSION"))
-        .author("BRAVO68WEB")
-        .about("A CLI tool for managing Minecraft projects")
-        .subcommand_required(true)
-        .arg_required_else_help(true)
-        .subcommand(commands::init::command())
-        .subcommand(commands::run::command())
-        .subcommand(commands::console::command())
-        .subcommand(commands::props::command())
-        .subcommand(commands::status::command())
-        .subcommand(commands::stop::command())
-        .subcommand(commands::mods::command())
-        .get_matches();
+    let matches = cli().get_matches();
+    utils::logging::init(matches.get_flag("quiet"), matches.get_flag("verbose"));
+    utils::network::set_offline(matches.get_flag("offline"));
+
+    // Resolve every relative path (mc.toml, mods/, server.properties) against the project
+    if let Some(path) = matches.get_one::<String>("config")
+        && let Err(e) = enter_project(Path::new(path))
+    {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
 
     // Delegate subcommand dispatch to commands::execute for consistency
-    commands::execute(&matches).await?;
+    if let Err(e) = commands::execute(&matches).await {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
 
     Ok(())
 }
+
+/// Change into the project directory named by `--config`
+fn enter_project(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let dir = if path.is_file() {
+        path.parent().unwrap_or(Path::new("."))
+    } else {
+        path
+    };
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    std::env::set_current_dir(dir)
+        .map_err(|e| format!("Cannot use project directory '{}': {}", dir.display(), e).into())
+}
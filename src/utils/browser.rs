@@ -0,0 +1,68 @@
+// Open web pages in the user's default browser
+use std::process::{Command, Stdio};
+use tracing::info;
+
+/// Build the program and arguments that open `url` with the default handler on `os`
+/// (as in `std::env::consts::OS`)
+pub fn open_command(url: &str, os: &str) -> (&'static str, Vec<String>) {
+    match os {
+        // The empty argument is `start`'s window title, so the URL is not taken for one
+        "windows" => (
+            "cmd",
+            vec![
+                "/C".to_string(),
+                "start".to_string(),
+                String::new(),
+                url.to_string(),
+            ],
+        ),
+        "macos" => ("open", vec![url.to_string()]),
+        _ => ("xdg-open", vec![url.to_string()]),
+    }
+}
+
+/// Whether a browser could be shown; on Linux and the BSDs that needs a graphical session
+fn has_display() -> bool {
+    cfg!(any(windows, target_os = "macos"))
+        || ["DISPLAY", "WAYLAND_DISPLAY"]
+            .iter()
+            .any(|v| std::env::var_os(v).is_some_and(|d| !d.is_empty()))
+}
+
+/// Open `url` in the default browser. On a headless system, or if no opener is
+/// installed, the URL is printed instead.
+pub fn open_or_print(url: &str) {
+    if has_display() {
+        let (program, args) = open_command(url, std::env::consts::OS);
+        let opened = Command::new(program)
+            .args(&args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .is_ok_and(|s| s.success());
+        if opened {
+            info!("Opened {}", url);
+            return;
+        }
+    }
+    println!("{}", url);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_command_per_platform() {
+        let url = "https://modrinth.com/project/lithium";
+        assert_eq!(
+            open_command(url, "linux"),
+            ("xdg-open", vec![url.to_string()])
+        );
+        assert_eq!(open_command(url, "macos"), ("open", vec![url.to_string()]));
+        let (program, args) = open_command(url, "windows");
+        assert_eq!(program, "cmd");
+        assert_eq!(args, ["/C", "start", "", url]);
+    }
+}
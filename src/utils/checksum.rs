@@ -0,0 +1,65 @@
+use sha2::{Digest, Sha256, Sha512};
+
+/// Compute the lowercase hex SHA-512 digest of the given bytes
+pub fn sha512_hex(bytes: &[u8]) -> String {
+    let digest = Sha512::digest(bytes);
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Compare bytes against an expected SHA-512 hex digest
+pub fn verify_sha512(bytes: &[u8], expected: &str) -> Result<(), String> {
+    let actual = sha512_hex(bytes);
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        Err(format!(
+            "SHA-512 mismatch: expected {}, got {}",
+            expected.trim(),
+            actual
+        ))
+    }
+}
+
+/// Compare bytes against an expected SHA-256 hex digest
+pub fn verify_sha256(bytes: &[u8], expected: &str) -> Result<(), String> {
+    let actual: String = Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    if actual.eq_ignore_ascii_case(expected.trim()) {
+        Ok(())
+    } else {
+        Err(format!(
+            "SHA-256 mismatch: expected {}, got {}",
+            expected.trim(),
+            actual
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABC_SHA512: &str = "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f";
+
+    #[test]
+    fn hash_known_vector() {
+        assert_eq!(sha512_hex(b"abc"), ABC_SHA512);
+    }
+
+    #[test]
+    fn verify_match_and_mismatch() {
+        assert!(verify_sha512(b"abc", ABC_SHA512).is_ok());
+        assert!(verify_sha512(b"abc", &ABC_SHA512.to_uppercase()).is_ok());
+        let err = verify_sha512(b"abd", ABC_SHA512).unwrap_err();
+        assert!(err.contains(ABC_SHA512));
+    }
+
+    #[test]
+    fn verify_sha256_known_vector() {
+        let abc = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+        assert!(verify_sha256(b"abc", abc).is_ok());
+        assert!(verify_sha256(b"abd", abc).is_err());
+    }
+}
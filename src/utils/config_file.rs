@@ -1,8 +1,12 @@
+use crate::error::{Error, Result};
+use crate::utils::java;
+use crate::utils::safe_write::write_with_backup;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
 use std::io;
 use std::path::Path;
+use std::str::FromStr;
 
 /// Main configuration structure for mc.toml
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -30,8 +34,59 @@ pub struct McConfig {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Versions {
     pub mc_version: String,
+    /// Loader version (Fabric or Quilt, per `loader_type`)
     pub fabric_version: String,
     pub mc_cli_version: String,
+    /// Mod loader in use: one of [`LOADER_TYPES`], or [`NO_LOADER`] on Paper
+    #[serde(default = "default_loader_type")]
+    pub loader_type: String,
+    /// Server software: one of [`SERVER_TYPES`]
+    #[serde(default = "default_server_type")]
+    pub server_type: String,
+}
+
+/// Server software mc-cli can install
+pub const SERVER_TYPES: &[&str] = &["fabric", "paper"];
+
+fn default_server_type() -> String {
+    String::from("fabric")
+}
+
+/// Mod loaders mc-cli can match Modrinth versions against
+pub const LOADER_TYPES: &[&str] = &["fabric", "quilt", "forge", "neoforge"];
+
+/// `loader_type` of servers without a mod loader, such as Paper
+pub const NO_LOADER: &str = "none";
+
+fn default_loader_type() -> String {
+    String::from("fabric")
+}
+
+impl Versions {
+    /// Whether the server runs a mod loader; Paper loads plugins, not mods
+    pub fn is_modded(&self) -> bool {
+        self.server_type != "paper" && self.loader_type != NO_LOADER
+    }
+
+    /// Modrinth loader names whose mods run on the configured loader
+    pub fn mod_loaders(&self) -> &'static [&'static str] {
+        match self.loader_type.as_str() {
+            // Quilt loads Fabric mods too
+            "quilt" => &["quilt", "fabric"],
+            "forge" => &["forge"],
+            "neoforge" => &["neoforge"],
+            NO_LOADER => &[],
+            _ => &["fabric"],
+        }
+    }
+
+    /// Whether a Modrinth version declaring these loaders runs on the configured loader
+    pub fn supports_loaders(&self, loaders: &[String]) -> bool {
+        let accepted = self.mod_loaders();
+        loaders
+            .iter()
+            .any(|l| accepted.iter().any(|m| l.eq_ignore_ascii_case(m)))
+    }
 }
 
 /// Mods section
@@ -59,39 +114,145 @@ pub struct Resourcepacks {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Console {
     pub launch_cmd: Vec<String>,
+    /// Java binary that replaces a leading `java` in launch_cmd, for hosts with several JDKs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub java_path: Option<String>,
 }
 
-impl McConfig {
-    /// Parse mc.toml file from the given path
-    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
-        let content = fs::read_to_string(path).map_err(ConfigError::Io)?;
+impl Console {
+    /// launch_cmd with a leading Java program replaced by `java_override`, else by
+    /// `java_path` when set
+    pub fn command(&self, java_override: Option<&str>) -> Vec<String> {
+        let mut cmd = self.launch_cmd.clone();
+        if let Some(java) = java_override.or(self.java_path.as_deref())
+            && let Some(program) = cmd.first_mut()
+            && java::is_java(program)
+        {
+            *program = java.to_string();
+        }
+        cmd
+    }
 
-        Self::from_str(&content)
+    /// Server jar launch_cmd runs: the argument after `-jar`, else any `.jar` argument
+    pub fn jar(&self) -> Option<&str> {
+        let cmd = &self.launch_cmd;
+        cmd.iter()
+            .position(|a| a == "-jar")
+            .and_then(|i| cmd.get(i + 1))
+            .or_else(|| cmd.iter().find(|a| a.ends_with(".jar")))
+            .map(String::as_str)
+    }
+}
+
+/// Kind of Modrinth project tracked in mc.toml
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectKind {
+    Mod,
+    Datapack,
+    Resourcepack,
+}
+
+impl ProjectKind {
+    /// Parse from a CLI value
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "mod" => Some(ProjectKind::Mod),
+            "datapack" => Some(ProjectKind::Datapack),
+            "resourcepack" => Some(ProjectKind::Resourcepack),
+            _ => None,
+        }
     }
 
-    /// Parse mc.toml from a string
-    pub fn from_str(content: &str) -> Result<Self, ConfigError> {
-        toml::from_str(content).map_err(ConfigError::Parse)
+    /// Name as used on the command line and in messages
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProjectKind::Mod => "mod",
+            ProjectKind::Datapack => "datapack",
+            ProjectKind::Resourcepack => "resourcepack",
+        }
+    }
+
+    /// Modrinth `project_type` of this kind; datapacks are published as mods
+    pub fn project_type(&self) -> &'static str {
+        match self {
+            ProjectKind::Mod | ProjectKind::Datapack => "mod",
+            ProjectKind::Resourcepack => "resourcepack",
+        }
+    }
+
+    /// Whether a version with the given loaders is of this kind. Datapacks are
+    /// told apart from mods by their `datapack` loader.
+    pub fn matches_loaders(&self, loaders: &[String]) -> bool {
+        let is_datapack = |l: &String| l.eq_ignore_ascii_case("datapack");
+        match self {
+            ProjectKind::Mod => loaders.is_empty() || !loaders.iter().all(is_datapack),
+            ProjectKind::Datapack => loaders.iter().any(is_datapack),
+            ProjectKind::Resourcepack => true,
+        }
+    }
+
+    /// Directory the project files are downloaded into
+    pub fn dir(&self) -> &'static str {
+        match self {
+            ProjectKind::Mod => "mods",
+            ProjectKind::Datapack => "datapacks",
+            ProjectKind::Resourcepack => "resourcepacks",
+        }
+    }
+}
+
+/// Parse mc.toml from a string
+impl FromStr for McConfig {
+    type Err = Error;
+
+    fn from_str(content: &str) -> Result<Self> {
+        Ok(toml::from_str(content).map_err(ConfigError::Parse)?)
+    }
+}
+
+impl McConfig {
+    /// Parse mc.toml file from the given path
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let content = read_config(path.as_ref())?;
+
+        content.parse()
     }
 
-    /// Save configuration to a file
-    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ConfigError> {
+    /// Save configuration to a file atomically, keeping the previous version as `<file>.bak`
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let content = toml::to_string_pretty(self).map_err(ConfigError::Serialize)?;
 
-        fs::write(path, content).map_err(ConfigError::Io)
+        Ok(write_with_backup(path, content)?)
     }
 
     /// Load mc.toml from the current directory
-    pub fn load() -> Result<Self, ConfigError> {
+    pub fn load() -> Result<Self> {
         Self::from_file("mc.toml")
     }
 
     /// Check if mc.toml exists in the current directory
-    #[allow(dead_code)]
     pub fn exists() -> bool {
         Path::new("mc.toml").exists()
     }
 
+    /// Installed entries for the given project kind
+    pub fn installed(&self, kind: ProjectKind) -> &HashMap<String, String> {
+        match kind {
+            ProjectKind::Mod => &self.mods.installed,
+            ProjectKind::Datapack => &self.datapacks.installed,
+            ProjectKind::Resourcepack => &self.resourcepacks.installed,
+        }
+    }
+
+    /// Mutable installed entries for the given project kind
+    pub fn installed_mut(&mut self, kind: ProjectKind) -> &mut HashMap<String, String> {
+        match kind {
+            ProjectKind::Mod => &mut self.mods.installed,
+            ProjectKind::Datapack => &mut self.datapacks.installed,
+            ProjectKind::Resourcepack => &mut self.resourcepacks.installed,
+        }
+    }
+
     /// Create a new default configuration
     pub fn new(name: String) -> Self {
         Self {
@@ -100,6 +261,8 @@ impl McConfig {
                 mc_version: String::from("1.20.1"),
                 fabric_version: String::from("0.15.0"),
                 mc_cli_version: String::from("0.1.0"),
+                loader_type: default_loader_type(),
+                server_type: default_server_type(),
             },
             mods: Mods {
                 installed: HashMap::new(),
@@ -119,31 +282,29 @@ impl McConfig {
                     String::from("server.jar"),
                     String::from("nogui"),
                 ],
+                java_path: None,
             },
         }
     }
 }
 
-/// Error types for configuration file operations
-#[derive(Debug)]
+/// Read a config file, reporting a missing one as [`Error::NotFound`]
+pub fn read_config(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|e| match e.kind() {
+        io::ErrorKind::NotFound => Error::NotFound(format!("{} not found", path.display())),
+        _ => Error::Io(e),
+    })
+}
+
+/// Error types for configuration file contents
+#[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
-    Io(io::Error),
+    #[error("Parse error: {0}")]
     Parse(toml::de::Error),
+    #[error("Serialize error: {0}")]
     Serialize(toml::ser::Error),
 }
 
-impl std::fmt::Display for ConfigError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            ConfigError::Io(e) => write!(f, "IO error: {}", e),
-            ConfigError::Parse(e) => write!(f, "Parse error: {}", e),
-            ConfigError::Serialize(e) => write!(f, "Serialize error: {}", e),
-        }
-    }
-}
-
-impl std::error::Error for ConfigError {}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -179,6 +340,7 @@ launch_cmd = ["java", "-Xmx4G", "-jar", "server.jar", "nogui"]
         assert_eq!(config.name, "my-minecraft-server");
         assert_eq!(config.versions.mc_version, "1.20.1");
         assert_eq!(config.versions.fabric_version, "0.15.0");
+        assert_eq!(config.versions.loader_type, "fabric");
         assert_eq!(config.mods.installed.len(), 3);
         assert_eq!(
             config.mods.installed.get("fabric-api"),
@@ -255,4 +417,104 @@ launch_cmd = ["java", "-Xmx4G", "-jar", "server.jar", "nogui"]
         assert!(toml_string.contains("asdf = \"1.2.3\""));
         assert!(toml_string.contains("qwerty = \"9.9.9\""));
     }
+
+    #[test]
+    fn test_missing_and_invalid_config_errors() {
+        let err = McConfig::from_file("does-not-exist/mc.toml").unwrap_err();
+        assert!(matches!(err, Error::NotFound(_)));
+
+        let err = McConfig::from_str("name = ").unwrap_err();
+        assert!(matches!(err, Error::Config(ConfigError::Parse(_))));
+    }
+
+    #[test]
+    fn test_quilt_mod_loaders() {
+        let mut config = McConfig::new(String::from("test"));
+        assert_eq!(config.versions.mod_loaders(), &["fabric"]);
+
+        config.versions.loader_type = String::from("quilt");
+        assert_eq!(config.versions.mod_loaders(), &["quilt", "fabric"]);
+    }
+
+    #[test]
+    fn test_installed_by_kind() {
+        let mut config = McConfig::new(String::from("test"));
+        config
+            .installed_mut(ProjectKind::Datapack)
+            .insert("dp".to_string(), "1.0.0".to_string());
+
+        assert!(config.installed(ProjectKind::Mod).is_empty());
+        assert_eq!(
+            config.datapacks.installed.get("dp"),
+            Some(&"1.0.0".to_string())
+        );
+        assert_eq!(
+            ProjectKind::parse("resourcepack"),
+            Some(ProjectKind::Resourcepack)
+        );
+        assert_eq!(ProjectKind::parse("plugin"), None);
+    }
+
+    #[test]
+    fn test_kind_matching() {
+        let loaders = |ls: &[&str]| ls.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+        assert_eq!(ProjectKind::Datapack.project_type(), "mod");
+        assert!(ProjectKind::Datapack.matches_loaders(&loaders(&["datapack"])));
+        assert!(!ProjectKind::Datapack.matches_loaders(&loaders(&["fabric"])));
+        assert!(ProjectKind::Mod.matches_loaders(&loaders(&["fabric", "datapack"])));
+        assert!(!ProjectKind::Mod.matches_loaders(&loaders(&["datapack"])));
+        assert!(ProjectKind::Resourcepack.matches_loaders(&loaders(&["minecraft"])));
+    }
+
+    #[test]
+    fn test_forge_loaders() {
+        let loaders = |ls: &[&str]| ls.iter().map(|l| l.to_string()).collect::<Vec<_>>();
+        let mut versions = Versions {
+            mc_version: "1.20.1".to_string(),
+            fabric_version: "47.2.0".to_string(),
+            mc_cli_version: "0.1.0".to_string(),
+            loader_type: "forge".to_string(),
+            server_type: default_server_type(),
+        };
+        assert!(versions.supports_loaders(&loaders(&["forge"])));
+        assert!(!versions.supports_loaders(&loaders(&["fabric", "quilt"])));
+
+        versions.loader_type = "neoforge".to_string();
+        assert!(versions.supports_loaders(&loaders(&["NeoForge"])));
+        assert!(!versions.supports_loaders(&loaders(&["forge"])));
+    }
+
+    #[test]
+    fn test_paper_has_no_mod_loader() {
+        let versions = Versions {
+            mc_version: "1.21.1".to_string(),
+            fabric_version: String::new(),
+            mc_cli_version: "0.1.0".to_string(),
+            loader_type: NO_LOADER.to_string(),
+            server_type: "paper".to_string(),
+        };
+        assert!(!versions.is_modded());
+        assert!(versions.mod_loaders().is_empty());
+        assert!(!versions.supports_loaders(&["fabric".to_string()]));
+    }
+
+    #[test]
+    fn test_console_command_java_path() {
+        let mut console = Console {
+            launch_cmd: vec!["java".into(), "-jar".into(), "server.jar".into()],
+            java_path: None,
+        };
+        assert_eq!(console.command(None)[0], "java");
+
+        console.java_path = Some("/opt/jdk21/bin/java".into());
+        assert_eq!(console.command(None)[0], "/opt/jdk21/bin/java");
+        assert_eq!(
+            console.command(Some("/opt/jdk17/bin/java"))[0],
+            "/opt/jdk17/bin/java"
+        );
+
+        // Wrapper scripts are left alone
+        console.launch_cmd = vec!["./start.sh".into()];
+        assert_eq!(console.command(None), vec!["./start.sh".to_string()]);
+    }
 }
@@ -0,0 +1,289 @@
+// Streamed HTTP downloads with a progress bar on stderr
+use crate::utils::checksum::verify_sha512;
+use crate::utils::network;
+use futures::StreamExt;
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tracing::{Level, debug};
+
+const USER_AGENT: &str = "BRAVO68WEB/mc-cli/0.1.0";
+
+/// Download `url` into memory, showing a progress bar labelled `label`
+pub async fn fetch(url: &str, label: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    fetch_in(url, label, None).await
+}
+
+/// Like [`fetch`], drawing the bar inside `multi` so concurrent downloads stack
+pub async fn fetch_in(
+    url: &str,
+    label: &str,
+    multi: Option<&MultiProgress>,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut bytes = Vec::new();
+    stream_into(url, label, multi, &mut bytes).await?;
+    Ok(bytes)
+}
+
+/// Download `url` to `<dest>.part`, checking its size against Content-Length and
+/// its SHA-512 when one is given. Returns the verified partial file for the caller
+/// to rename into place; on failure the partial file is removed.
+pub async fn fetch_part(
+    url: &str,
+    dest: &Path,
+    sha512: Option<&str>,
+    multi: Option<&MultiProgress>,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let part = part_path(dest);
+    let result = write_part(url, dest, &part, sha512, multi).await;
+    if result.is_err() {
+        let _ = fs::remove_file(&part);
+    }
+    result.map(|()| part)
+}
+
+/// Download `url` to `dest` via [`fetch_part`], so `dest` is either the complete,
+/// verified file or left untouched
+pub async fn fetch_to_file(
+    url: &str,
+    dest: &Path,
+    sha512: Option<&str>,
+    multi: Option<&MultiProgress>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let part = fetch_part(url, dest, sha512, multi).await?;
+    finish_part(&part, dest)?;
+    Ok(())
+}
+
+/// Move a file returned by [`fetch_part`] into place, removing it if that fails
+pub fn finish_part(part: &Path, dest: &Path) -> std::io::Result<()> {
+    fs::rename(part, dest).inspect_err(|_| {
+        let _ = fs::remove_file(part);
+    })
+}
+
+/// Whether `bytes` start with a zip local file header (or are an empty zip), as
+/// every jar, datapack and resourcepack does
+pub fn is_zip(bytes: &[u8]) -> bool {
+    bytes.starts_with(b"PK\x03\x04") || bytes.starts_with(b"PK\x05\x06")
+}
+
+/// Content types a CDN or proxy sends for error pages rather than files
+fn is_error_page(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or("").trim();
+    mime.starts_with("text/") || mime.ends_with("/json") || mime.ends_with("/xml")
+}
+
+/// `<dest>.part`, e.g. mods/sodium.jar -> mods/sodium.jar.part
+fn part_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().map(OsString::from).unwrap_or_default();
+    name.push(".part");
+    dest.with_file_name(name)
+}
+
+async fn write_part(
+    url: &str,
+    dest: &Path,
+    part: &Path,
+    sha512: Option<&str>,
+    multi: Option<&MultiProgress>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let label = dest
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| dest.display().to_string());
+    let mut file = BufWriter::new(File::create(part)?);
+    stream_into(url, &label, multi, &mut file).await?;
+    file.into_inner()?.sync_all()?;
+
+    let bytes = fs::read(part)?;
+    if !is_zip(&bytes) {
+        return Err(format!(
+            "Downloaded '{}' is not a zip or jar archive; the server may have sent an error page",
+            label
+        )
+        .into());
+    }
+    if let Some(expected) = sha512
+        && let Err(e) = verify_sha512(&bytes, expected)
+    {
+        return Err(format!("Downloaded '{}' is corrupt. {}", label, e).into());
+    }
+    Ok(())
+}
+
+/// Stream the body of `url` into `sink`, failing if it ends before Content-Length
+async fn stream_into(
+    url: &str,
+    label: &str,
+    multi: Option<&MultiProgress>,
+    sink: &mut impl Write,
+) -> Result<(), Box<dyn std::error::Error>> {
+    network::require_online(&format!("Downloading {}", label))?;
+    debug!("Downloading {}", url);
+    let client = network::download_client(USER_AGENT, network::REQUEST_TIMEOUT)?;
+    let response = network::send(client.get(url)).await?;
+    // Redirects (e.g. to a CDN) are followed, so report the URL that actually answered
+    let status = response.status();
+    let final_url = response.url().clone();
+    if final_url.as_str() != url {
+        debug!("{} redirected to {}", url, final_url);
+    }
+    if !status.is_success() {
+        return Err(format!("Download failed with status {} for {}", status, final_url).into());
+    }
+    if let Some(content_type) = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .filter(|ct| is_error_page(ct))
+    {
+        return Err(format!(
+            "Download of {} returned {} instead of a file ({})",
+            label, content_type, final_url
+        )
+        .into());
+    }
+
+    // Fall back to a spinner when the server does not send Content-Length
+    let total = response.content_length();
+    let bar = match total {
+        Some(len) => ProgressBar::new(len).with_style(
+            ProgressStyle::with_template(
+                "{msg} [{bar:30}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})",
+            )?
+            .progress_chars("=> "),
+        ),
+        None => ProgressBar::new_spinner()
+            .with_style(ProgressStyle::with_template("{spinner} {msg} {bytes}")?),
+    };
+    let bar = match multi {
+        Some(m) => m.add(bar),
+        None => bar,
+    };
+    if !tracing::enabled!(Level::INFO) {
+        bar.set_draw_target(ProgressDrawTarget::hidden());
+    }
+    bar.set_message(label.to_string());
+
+    let mut received = 0u64;
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = match chunk {
+            Ok(c) => c,
+            Err(e) => {
+                bar.abandon();
+                return Err(e.into());
+            }
+        };
+        if let Err(e) = sink.write_all(&chunk) {
+            bar.abandon();
+            return Err(e.into());
+        }
+        received += chunk.len() as u64;
+        bar.set_position(received);
+    }
+    if let Some(expected) = total
+        && received != expected
+    {
+        bar.abandon();
+        return Err(format!(
+            "Download of {} was cut short: got {} of {} bytes",
+            label, received, expected
+        )
+        .into());
+    }
+    bar.finish_and_clear();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::checksum::sha512_hex;
+    use crate::utils::mock_http::serve_sequence;
+
+    /// Serve each reply to one connection, in order; returns the URL of a jar
+    async fn serve(replies: Vec<String>) -> String {
+        format!("{}/mod.jar", serve_sequence(replies).await)
+    }
+
+    const JAR: &[u8] = b"PK\x03\x04jar";
+
+    #[tokio::test]
+    async fn test_fetch_to_file_leaves_no_partial_jar() {
+        let dir = std::env::temp_dir().join(format!("mc-cli_download_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let dest = dir.join("mod.jar");
+        let ok = "HTTP/1.1 200 OK\r\nContent-Length: 7\r\nConnection: close\r\n\r\nPK\x03\x04jar";
+        let url = serve(vec![
+            "HTTP/1.1 200 OK\r\nContent-Length: 10\r\nConnection: close\r\n\r\nabc".to_string(),
+            ok.to_string(),
+            ok.to_string(),
+        ])
+        .await;
+
+        // Body cut short
+        assert!(fetch_to_file(&url, &dest, None, None).await.is_err());
+        assert!(!dest.exists() && !part_path(&dest).exists());
+
+        // Hash mismatch
+        let err = fetch_to_file(&url, &dest, Some(&sha512_hex(b"abd")), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("corrupt"));
+        assert!(!dest.exists() && !part_path(&dest).exists());
+
+        fetch_to_file(&url, &dest, Some(&sha512_hex(JAR)), None)
+            .await
+            .unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), JAR);
+        assert!(!part_path(&dest).exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fetch_follows_redirect_and_rejects_error_pages() {
+        let dir = std::env::temp_dir().join(format!("mc-cli_redirect_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let dest = dir.join("mod.jar");
+        let redirect = "HTTP/1.1 302 Found\r\nLocation: /cdn/mod.jar\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+        let url = serve(vec![
+            redirect.to_string(),
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: 6\r\nConnection: close\r\n\r\n<html>".to_string(),
+            redirect.to_string(),
+            "HTTP/1.1 200 OK\r\nContent-Type: application/octet-stream\r\nContent-Length: 4\r\nConnection: close\r\n\r\noops".to_string(),
+            redirect.to_string(),
+            "HTTP/1.1 200 OK\r\nContent-Type: application/java-archive\r\nContent-Length: 7\r\nConnection: close\r\n\r\nPK\x03\x04jar".to_string(),
+        ])
+        .await;
+
+        let err = fetch_to_file(&url, &dest, None, None).await.unwrap_err();
+        assert!(err.to_string().contains("text/html"));
+        assert!(err.to_string().contains("/cdn/mod.jar"));
+        assert!(!dest.exists() && !part_path(&dest).exists());
+
+        let err = fetch_to_file(&url, &dest, None, None).await.unwrap_err();
+        assert!(err.to_string().contains("not a zip"));
+        assert!(!dest.exists() && !part_path(&dest).exists());
+
+        fetch_to_file(&url, &dest, None, None).await.unwrap();
+        assert_eq!(fs::read(&dest).unwrap(), JAR);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_is_zip_and_error_page() {
+        assert!(is_zip(JAR));
+        assert!(is_zip(b"PK\x05\x06"));
+        assert!(!is_zip(b"<!DOCTYPE html>"));
+        assert!(is_error_page("text/html; charset=utf-8"));
+        assert!(is_error_page("application/json"));
+        assert!(!is_error_page("application/java-archive"));
+        assert!(!is_error_page("application/octet-stream"));
+    }
+}
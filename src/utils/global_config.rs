@@ -0,0 +1,140 @@
+// User-wide defaults from ~/.config/mc-cli/config.toml, applied under mc.toml and CLI values
+use crate::error::{Error, Result};
+use crate::utils::config_file::{ConfigError, read_config};
+use crate::utils::java;
+use crate::utils::paths;
+use crate::utils::safe_write::write_private;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+/// Keys accepted by `config set-default`
+pub const KEYS: &[&str] = &[
+    "memory",
+    "min_memory",
+    "jvm_flags",
+    "java_path",
+    "loader_type",
+    "modrinth_token",
+];
+
+/// Defaults used when neither the command line nor mc.toml sets a value
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GlobalConfig {
+    /// Maximum JVM heap for `init`, e.g. 4G
+    pub memory: Option<String>,
+    /// Initial JVM heap for `init`
+    pub min_memory: Option<String>,
+    /// Extra space-separated JVM flags for `init`
+    pub jvm_flags: Option<String>,
+    /// Java binary stored by `init` as console.java_path
+    pub java_path: Option<String>,
+    /// Mod loader `init` installs on a fabric-type server: fabric or quilt
+    pub loader_type: Option<String>,
+    /// Modrinth API token, used when `MODRINTH_TOKEN` is not set
+    pub modrinth_token: Option<String>,
+}
+
+/// Parse the global config from a string
+impl FromStr for GlobalConfig {
+    type Err = Error;
+
+    fn from_str(content: &str) -> Result<Self> {
+        Ok(toml::from_str(content).map_err(ConfigError::Parse)?)
+    }
+}
+
+impl GlobalConfig {
+    /// Location of the global config file, if a home directory can be determined
+    pub fn path() -> Option<PathBuf> {
+        paths::config_dir().map(|d| d.join("config.toml"))
+    }
+
+    /// Load the global config, or empty defaults if there is none
+    pub fn load() -> Result<Self> {
+        match Self::path() {
+            Some(path) if path.exists() => read_config(&path)?.parse(),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Save the global config, creating its directory if needed. It can hold the
+    /// Modrinth token, so it is written owner-only and without a backup copy
+    pub fn save(&self) -> Result<PathBuf> {
+        let path = Self::path().ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "no home directory to store the global config in",
+            )
+        })?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let content = toml::to_string_pretty(self).map_err(ConfigError::Serialize)?;
+        write_private(&path, content)?;
+        Ok(path)
+    }
+
+    /// Set one of [`KEYS`], validating the value; an empty value clears the key
+    pub fn set(&mut self, key: &str, value: &str) -> std::result::Result<(), String> {
+        let value = value.trim();
+        let value = if value.is_empty() {
+            None
+        } else {
+            match key {
+                "memory" | "min_memory" => Some(java::parse_memory(value)?),
+                "loader_type" if !["fabric", "quilt"].contains(&value) => {
+                    return Err(format!(
+                        "'{}' is not a valid loader_type; expected fabric or quilt",
+                        value
+                    ));
+                }
+                _ => Some(value.to_string()),
+            }
+        };
+        let slot = match key {
+            "memory" => &mut self.memory,
+            "min_memory" => &mut self.min_memory,
+            "jvm_flags" => &mut self.jvm_flags,
+            "java_path" => &mut self.java_path,
+            "loader_type" => &mut self.loader_type,
+            "modrinth_token" => &mut self.modrinth_token,
+            _ => {
+                return Err(format!(
+                    "Unknown key '{}'; expected one of: {}",
+                    key,
+                    KEYS.join(", ")
+                ));
+            }
+        };
+        *slot = value;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_validates_and_clears() {
+        let mut config = GlobalConfig::default();
+        config.set("memory", "4G").unwrap();
+        config.set("loader_type", "quilt").unwrap();
+        config
+            .set("jvm_flags", "-XX:+UseG1GC -XX:MaxGCPauseMillis=200")
+            .unwrap();
+        assert_eq!(config.memory.as_deref(), Some("4G"));
+        assert_eq!(config.loader_type.as_deref(), Some("quilt"));
+
+        assert!(config.set("memory", "lots").is_err());
+        assert!(config.set("loader_type", "forge").is_err());
+        assert!(config.set("colour", "red").is_err());
+
+        config.set("memory", "").unwrap();
+        assert_eq!(config.memory, None);
+
+        let saved = toml::to_string_pretty(&config).unwrap();
+        assert_eq!(GlobalConfig::from_str(&saved).unwrap(), config);
+    }
+}
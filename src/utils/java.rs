@@ -0,0 +1,121 @@
+// Java runtime detection, so an outdated JVM is reported before the server crashes
+use std::path::Path;
+use std::process::Command;
+
+/// Whether a launch command's program is a Java binary (`java`, `/opt/jdk/bin/java.exe`, ...)
+pub fn is_java(program: &str) -> bool {
+    Path::new(program)
+        .file_stem()
+        .is_some_and(|s| s.to_string_lossy().eq_ignore_ascii_case("java"))
+}
+
+/// Major Java version reported by `<java> -version`, if it runs at all
+pub fn major_version(java: &str) -> Option<u32> {
+    let output = Command::new(java).arg("-version").output().ok()?;
+    // The version banner goes to stderr, but some builds print it to stdout
+    parse_major(&String::from_utf8_lossy(&output.stderr))
+        .or_else(|| parse_major(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Parse the major version from `-version` output, e.g. `openjdk version "17.0.2"`
+/// or `java version "1.8.0_292"` (legacy `1.x` numbering)
+pub fn parse_major(output: &str) -> Option<u32> {
+    let quoted = output.split('"').nth(1)?;
+    let mut parts = quoted.split(['.', '_', '-', '+']);
+    let first: u32 = parts.next()?.parse().ok()?;
+    if first == 1 {
+        parts.next()?.parse().ok()
+    } else {
+        Some(first)
+    }
+}
+
+/// Minimum Java major version for a Minecraft release; `None` for unrecognised
+/// versions such as snapshots
+pub fn required_for(mc_version: &str) -> Option<u32> {
+    let mut parts = mc_version.split('.');
+    if parts.next()? != "1" {
+        return None;
+    }
+    let minor: u32 = parts.next()?.parse().ok()?;
+    let patch: u32 = match parts.next() {
+        Some(p) => p.parse().ok()?,
+        None => 0,
+    };
+    Some(match (minor, patch) {
+        (21.., _) | (20, 5..) => 21,
+        (18.., _) => 17,
+        (17, _) => 16,
+        _ => 8,
+    })
+}
+
+/// Validate a JVM memory size such as `4G` or `512M`
+pub fn parse_memory(value: &str) -> Result<String, String> {
+    let digits = value.trim_end_matches(['M', 'G']);
+    if value.len() == digits.len() + 1
+        && !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit())
+    {
+        Ok(value.to_string())
+    } else {
+        Err(format!(
+            "'{}' is not a valid memory size; expected a number followed by M or G, e.g. 4G",
+            value
+        ))
+    }
+}
+
+/// Check that `java` can run the given Minecraft version. A mismatch is a warning,
+/// or an error when `strict` is set.
+pub fn check(java: &str, mc_version: &str, strict: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let problem = match (major_version(java), required_for(mc_version)) {
+        (None, _) => format!("Could not run '{} -version'; is Java installed?", java),
+        (Some(found), Some(required)) if found < required => format!(
+            "Minecraft {} needs Java {} or newer, but '{}' is Java {}",
+            mc_version, required, java, found
+        ),
+        _ => return Ok(()),
+    };
+    if strict {
+        return Err(problem.into());
+    }
+    tracing::warn!("{}", problem);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_major() {
+        assert_eq!(
+            parse_major("openjdk version \"17.0.2\" 2022-01-18\nOpenJDK Runtime Environment"),
+            Some(17)
+        );
+        assert_eq!(parse_major("java version \"1.8.0_292\""), Some(8));
+        assert_eq!(parse_major("openjdk version \"21\" 2023-09-19"), Some(21));
+        assert_eq!(parse_major("openjdk version \"22-ea\""), Some(22));
+        assert_eq!(parse_major("no version here"), None);
+    }
+
+    #[test]
+    fn test_is_java() {
+        assert!(is_java("java"));
+        assert!(is_java("/usr/lib/jvm/java-21/bin/java"));
+        assert!(is_java("java.exe"));
+        assert!(!is_java("sh"));
+    }
+
+    #[test]
+    fn test_required_for() {
+        assert_eq!(required_for("1.21.1"), Some(21));
+        assert_eq!(required_for("1.20.5"), Some(21));
+        assert_eq!(required_for("1.20.4"), Some(17));
+        assert_eq!(required_for("1.18"), Some(17));
+        assert_eq!(required_for("1.17.1"), Some(16));
+        assert_eq!(required_for("1.16.5"), Some(8));
+        assert_eq!(required_for("24w14a"), None);
+    }
+}
@@ -0,0 +1,52 @@
+// Diagnostics via tracing; command results and prompts still use println! directly
+use std::fmt;
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Filter used when neither a flag nor RUST_LOG is given
+const DEFAULT_FILTER: &str = "mc_cli=info";
+
+/// Install the global subscriber, writing to stderr.
+/// `--quiet`/`--verbose` win over RUST_LOG, which wins over the default.
+pub fn init(quiet: bool, verbose: bool) {
+    let filter = if quiet {
+        EnvFilter::new("mc_cli=error")
+    } else if verbose {
+        EnvFilter::new("mc_cli=debug")
+    } else {
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER))
+    };
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .event_format(Plain)
+        .init();
+}
+
+/// Prints messages the way the CLI always has: bare for info, prefixed otherwise
+struct Plain;
+
+impl<S, N> FormatEvent<S, N> for Plain
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        match *event.metadata().level() {
+            Level::ERROR => write!(writer, "Error: ")?,
+            Level::WARN => write!(writer, "Warning: ")?,
+            Level::INFO => {}
+            _ => write!(writer, "[{}] ", event.metadata().target())?,
+        }
+        ctx.field_format().format_fields(writer.by_ref(), event)?;
+        writeln!(writer)
+    }
+}
@@ -0,0 +1,86 @@
+// Handling of Minecraft `§` formatting codes in server output
+
+/// ANSI SGR parameters for a formatting code character, if it has a terminal equivalent
+fn ansi_code(code: char) -> Option<&'static str> {
+    Some(match code.to_ascii_lowercase() {
+        '0' => "30",
+        '1' => "34",
+        '2' => "32",
+        '3' => "36",
+        '4' => "31",
+        '5' => "35",
+        '6' => "33",
+        '7' => "37",
+        '8' => "90",
+        '9' => "94",
+        'a' => "92",
+        'b' => "96",
+        'c' => "91",
+        'd' => "95",
+        'e' => "93",
+        'f' => "97",
+        'l' => "1",
+        'm' => "9",
+        'n' => "4",
+        'o' => "3",
+        'r' => "0",
+        _ => return None,
+    })
+}
+
+/// Rewrite `§x` sequences, letting `replace` map each code to its substitute
+fn rewrite(text: &str, mut replace: impl FnMut(char, &mut String)) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '§' {
+            if let Some(code) = chars.next() {
+                replace(code, &mut out);
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Remove all `§x` formatting codes
+pub fn strip_codes(text: &str) -> String {
+    rewrite(text, |_, _| {})
+}
+
+/// Translate `§x` formatting codes to ANSI escapes, dropping those without one
+pub fn to_ansi(text: &str) -> String {
+    let mut styled = false;
+    let mut out = rewrite(text, |code, out| {
+        if let Some(sgr) = ansi_code(code) {
+            out.push_str("\x1b[");
+            out.push_str(sgr);
+            out.push('m');
+            styled = true;
+        }
+    });
+    if styled {
+        out.push_str("\x1b[0m");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_codes() {
+        assert_eq!(strip_codes("§aGreen §lbold§r text"), "Green bold text");
+        assert_eq!(strip_codes("no codes"), "no codes");
+        assert_eq!(strip_codes("trailing §"), "trailing ");
+    }
+
+    #[test]
+    fn translates_codes_to_ansi() {
+        assert_eq!(to_ansi("§cred"), "\x1b[91mred\x1b[0m");
+        assert_eq!(to_ansi("§kmagic"), "magic");
+        assert_eq!(to_ansi("plain"), "plain");
+    }
+}
@@ -1,6 +1,11 @@
+use crate::utils::safe_write::write_with_backup;
 use std::fs;
 use std::io;
 use std::path::Path;
+use std::str::FromStr;
+
+/// Port the server listens on when server.properties does not say
+pub const DEFAULT_SERVER_PORT: u16 = 25565;
 
 #[derive(Debug, Clone)]
 enum Line {
@@ -12,19 +17,30 @@ enum Line {
 #[derive(Debug, Clone)]
 pub struct ServerProperties {
     lines: Vec<Line>,
+    /// Line ending used by the source file ("\n" or "\r\n")
+    newline: &'static str,
 }
 
-impl ServerProperties {
-    /// Parse server.properties from string contents
-    pub fn from_str(contents: &str) -> Result<Self, PropsError> {
+/// Parse server.properties from string contents, keeping comments, blank
+/// lines and key order so that saving reproduces the original layout
+impl FromStr for ServerProperties {
+    type Err = PropsError;
+
+    fn from_str(contents: &str) -> Result<Self, PropsError> {
+        let newline = if contents.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
         let mut lines = Vec::new();
-        for raw in contents.split('\n') {
+        for raw in contents.lines() {
             let line = raw.to_string();
             if line.trim().is_empty() {
                 lines.push(Line::Empty);
                 continue;
             }
-            if line.starts_with('#') {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with('#') || trimmed.starts_with('!') {
                 lines.push(Line::Comment(line));
                 continue;
             }
@@ -38,13 +54,15 @@ impl ServerProperties {
                 lines.push(Line::Comment(line));
             }
         }
-        Ok(Self { lines })
+        Ok(Self { lines, newline })
     }
+}
 
+impl ServerProperties {
     /// Read server.properties from a file path
     pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, PropsError> {
         let contents = fs::read_to_string(path).map_err(PropsError::IoError)?;
-        Self::from_str(&contents)
+        contents.parse()
     }
 
     /// Get a property value by key (first occurrence)
@@ -59,7 +77,31 @@ impl ServerProperties {
         None
     }
 
-    /// Set or update a property value
+    /// Address the server binds: `server-ip` (default all interfaces) and `server-port`
+    pub fn bind_address(&self) -> Result<(String, u16), String> {
+        let ip = self
+            .get("server-ip")
+            .filter(|ip| !ip.trim().is_empty())
+            .unwrap_or_else(|| String::from("0.0.0.0"));
+        let port = match self.get("server-port") {
+            Some(p) => p
+                .trim()
+                .parse::<u16>()
+                .map_err(|_| format!("server-port '{}' is not a valid port", p))?,
+            None => DEFAULT_SERVER_PORT,
+        };
+        Ok((ip, port))
+    }
+
+    /// Iterate over all key/value pairs in file order
+    pub fn entries(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.lines.iter().filter_map(|l| match l {
+            Line::Prop { key, value } => Some((key.as_str(), value.as_str())),
+            _ => None,
+        })
+    }
+
+    /// Set or update a property value in place; new keys are appended at the end
     pub fn set(&mut self, key: impl Into<String>, value: impl Into<String>) {
         let k = key.into();
         let v = value.into();
@@ -75,7 +117,6 @@ impl ServerProperties {
     }
 
     /// Remove the first occurrence of a property by key
-    #[allow(dead_code)]
     pub fn remove(&mut self, key: &str) -> bool {
         if let Some(idx) = self
             .lines
@@ -91,13 +132,105 @@ impl ServerProperties {
 
     // Removed inherent to_string per clippy; Display is implemented below
 
-    /// Save properties to a file path
+    /// Save properties to a file path atomically, keeping the previous version as `<file>.bak`
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), PropsError> {
         use std::fmt::Write as _;
         let mut s = String::new();
         // Render with Display implementation
         write!(&mut s, "{}", self).map_err(|e| PropsError::ParseError(e.to_string()))?;
-        fs::write(path, s).map_err(PropsError::IoError)
+        write_with_backup(path, s).map_err(PropsError::IoError)
+    }
+}
+
+/// Expected value type of a well-known server.properties key
+#[derive(Debug, Clone, Copy)]
+pub enum PropType {
+    Bool,
+    Port,
+    Int,
+    Enum(&'static [&'static str]),
+}
+
+const DIFFICULTIES: &[&str] = &["peaceful", "easy", "normal", "hard"];
+const GAMEMODES: &[&str] = &["survival", "creative", "adventure", "spectator"];
+
+const KNOWN_PROPS: &[(&str, PropType)] = &[
+    ("accepts-transfers", PropType::Bool),
+    ("allow-flight", PropType::Bool),
+    ("allow-nether", PropType::Bool),
+    ("broadcast-console-to-ops", PropType::Bool),
+    ("broadcast-rcon-to-ops", PropType::Bool),
+    ("difficulty", PropType::Enum(DIFFICULTIES)),
+    ("enable-command-block", PropType::Bool),
+    ("enable-jmx-monitoring", PropType::Bool),
+    ("enable-query", PropType::Bool),
+    ("enable-rcon", PropType::Bool),
+    ("enable-status", PropType::Bool),
+    ("enforce-secure-profile", PropType::Bool),
+    ("enforce-whitelist", PropType::Bool),
+    ("entity-broadcast-range-percentage", PropType::Int),
+    ("force-gamemode", PropType::Bool),
+    ("function-permission-level", PropType::Int),
+    ("gamemode", PropType::Enum(GAMEMODES)),
+    ("generate-structures", PropType::Bool),
+    ("hardcore", PropType::Bool),
+    ("hide-online-players", PropType::Bool),
+    ("log-ips", PropType::Bool),
+    ("max-chained-neighbor-updates", PropType::Int),
+    ("max-players", PropType::Int),
+    ("max-tick-time", PropType::Int),
+    ("max-world-size", PropType::Int),
+    ("network-compression-threshold", PropType::Int),
+    ("online-mode", PropType::Bool),
+    ("op-permission-level", PropType::Int),
+    ("player-idle-timeout", PropType::Int),
+    ("prevent-proxy-connections", PropType::Bool),
+    ("pvp", PropType::Bool),
+    ("query.port", PropType::Port),
+    ("rate-limit", PropType::Int),
+    ("rcon.port", PropType::Port),
+    ("require-resource-pack", PropType::Bool),
+    ("server-port", PropType::Port),
+    ("simulation-distance", PropType::Int),
+    ("spawn-animals", PropType::Bool),
+    ("spawn-monsters", PropType::Bool),
+    ("spawn-npcs", PropType::Bool),
+    ("spawn-protection", PropType::Int),
+    ("sync-chunk-writes", PropType::Bool),
+    ("use-native-transport", PropType::Bool),
+    ("view-distance", PropType::Int),
+    ("white-list", PropType::Bool),
+];
+
+impl PropType {
+    /// Look up the expected type of a well-known key
+    pub fn for_key(key: &str) -> Option<Self> {
+        KNOWN_PROPS.iter().find(|(k, _)| *k == key).map(|(_, t)| *t)
+    }
+
+    /// Check a value against this type, describing the allowed values on failure
+    pub fn validate(&self, value: &str) -> Result<(), String> {
+        let ok = match self {
+            PropType::Bool => value == "true" || value == "false",
+            PropType::Port => value.parse::<u16>().is_ok_and(|p| p > 0),
+            PropType::Int => value.parse::<i64>().is_ok(),
+            // Legacy numeric ids (e.g. difficulty=2) are still accepted by the server
+            PropType::Enum(allowed) => {
+                allowed.contains(&value) || value.parse::<usize>().is_ok_and(|i| i < allowed.len())
+            }
+        };
+        if ok {
+            return Ok(());
+        }
+        let expected = match self {
+            PropType::Bool => "true, false".to_string(),
+            PropType::Port => "a port number between 1 and 65535".to_string(),
+            PropType::Int => "an integer".to_string(),
+            PropType::Enum(allowed) => {
+                format!("{} (or 0-{})", allowed.join(", "), allowed.len() - 1)
+            }
+        };
+        Err(format!("'{}' is not valid; expected {}", value, expected))
     }
 }
 
@@ -122,16 +255,11 @@ impl std::fmt::Display for ServerProperties {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for l in &self.lines {
             match l {
-                Line::Comment(c) => {
-                    writeln!(f, "{}", c)?;
-                }
-                Line::Empty => {
-                    writeln!(f)?;
-                }
-                Line::Prop { key, value } => {
-                    writeln!(f, "{}={}", key, value)?;
-                }
+                Line::Comment(c) => write!(f, "{}", c)?,
+                Line::Empty => {}
+                Line::Prop { key, value } => write!(f, "{}={}", key, value)?,
             }
+            f.write_str(self.newline)?;
         }
         Ok(())
     }
@@ -159,6 +287,20 @@ motd=A Minecraft Server initialized by mc-cli
         );
     }
 
+    #[test]
+    fn save_preserves_layout() {
+        let generated = "#Minecraft server properties\r\n#Mon Jan 01 00:00:00 UTC 2024\r\n\r\npvp=true\r\nmotd=hi\r\n";
+        let mut props = ServerProperties::from_str(generated).unwrap();
+        assert_eq!(props.to_string(), generated);
+
+        props.set("motd", "hello");
+        props.set("new-key", "1");
+        assert_eq!(
+            props.to_string(),
+            "#Minecraft server properties\r\n#Mon Jan 01 00:00:00 UTC 2024\r\n\r\npvp=true\r\nmotd=hello\r\nnew-key=1\r\n"
+        );
+    }
+
     #[test]
     fn update_and_append_properties() {
         let mut props = ServerProperties::from_str(SAMPLE).unwrap();
@@ -172,6 +314,44 @@ motd=A Minecraft Server initialized by mc-cli
         assert!(s.contains("# Minecraft server properties"));
     }
 
+    #[test]
+    fn list_entries_skips_comments() {
+        let props = ServerProperties::from_str(SAMPLE).unwrap();
+        let keys: Vec<&str> = props.entries().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec!["max-tick-time", "view-distance", "motd"]);
+    }
+
+    #[test]
+    fn validate_known_property_types() {
+        let difficulty = PropType::for_key("difficulty").unwrap();
+        assert!(difficulty.validate("hard").is_ok());
+        assert!(difficulty.validate("2").is_ok());
+        assert!(difficulty.validate("4").is_err());
+        assert!(
+            difficulty
+                .validate("banana")
+                .unwrap_err()
+                .contains("peaceful")
+        );
+
+        let port = PropType::for_key("server-port").unwrap();
+        assert!(port.validate("25565").is_ok());
+        assert!(port.validate("notanumber").is_err());
+        assert!(port.validate("70000").is_err());
+
+        let pvp = PropType::for_key("pvp").unwrap();
+        assert!(pvp.validate("false").is_ok());
+        assert!(pvp.validate("yes").is_err());
+
+        assert!(
+            PropType::for_key("max-tick-time")
+                .unwrap()
+                .validate("-1")
+                .is_ok()
+        );
+        assert!(PropType::for_key("custom-key").is_none());
+    }
+
     #[test]
     fn load_and_save_file() {
         // Prepare temp input/output files to avoid repo-relative paths
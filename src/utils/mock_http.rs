@@ -0,0 +1,63 @@
+// Minimal HTTP servers for client tests: by path for API routes, or in sequence
+// for retries, redirects and malformed responses
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Serve `routes` as `(path, status line, JSON body)` until the test ends; other
+/// paths get a 404 and query strings are ignored. Returns the base URL, e.g.
+/// `http://127.0.0.1:1234`.
+pub async fn serve(routes: Vec<(&'static str, &'static str, &'static str)>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let request = read_request(&mut socket).await;
+            let request = String::from_utf8_lossy(&request);
+            let target = request.split_whitespace().nth(1).unwrap_or("/");
+            let path = target.split('?').next().unwrap_or(target);
+            let (status, body) = routes
+                .iter()
+                .find(|(p, _, _)| *p == path)
+                .map_or(("404 Not Found", ""), |(_, status, body)| (*status, *body));
+            let reply = format!(
+                "HTTP/1.1 {}\r\nContent-Type: application/json\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            let _ = socket.write_all(reply.as_bytes()).await;
+        }
+    });
+    format!("http://{}", addr)
+}
+
+/// Answer each connection with the next raw HTTP response in `replies`, whatever
+/// was requested, then stop accepting. Returns the base URL.
+pub async fn serve_sequence(replies: Vec<String>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        for reply in replies {
+            let Ok((mut socket, _)) = listener.accept().await else {
+                return;
+            };
+            read_request(&mut socket).await;
+            let _ = socket.write_all(reply.as_bytes()).await;
+        }
+    });
+    format!("http://{}", addr)
+}
+
+/// Read a request up to the end of its headers
+async fn read_request(socket: &mut TcpStream) -> Vec<u8> {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+        match socket.read(&mut buf).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => request.extend_from_slice(&buf[..n]),
+        }
+    }
+    request
+}
@@ -1,5 +1,22 @@
+pub mod browser;
+pub mod checksum;
 pub mod config_file;
 pub mod console_log;
+pub mod download;
+pub mod global_config;
+pub mod java;
+pub mod logging;
+pub mod mc_format;
 pub mod mc_server_props;
+#[cfg(test)]
+pub mod mock_http;
+pub mod mod_lock;
+pub mod network;
+pub mod paths;
+pub mod pid_file;
+pub mod process;
 pub mod rcon;
 pub mod runner;
+pub mod safe_write;
+pub mod slp;
+pub mod world;
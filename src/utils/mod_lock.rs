@@ -0,0 +1,124 @@
+use crate::error::{Error, Result};
+use crate::utils::config_file::{ConfigError, read_config};
+use crate::utils::pid_file;
+use crate::utils::safe_write::write_with_backup;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::str::FromStr;
+
+/// Mod lockfile (mc.lock) pinning the exact artifact installed for each mod
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ModLock {
+    #[serde(default)]
+    pub mods: Vec<LockedMod>,
+}
+
+/// Parse a lockfile from a string
+impl FromStr for ModLock {
+    type Err = Error;
+
+    fn from_str(content: &str) -> Result<Self> {
+        Ok(toml::from_str(content).map_err(ConfigError::Parse)?)
+    }
+}
+
+/// A single resolved mod artifact
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LockedMod {
+    pub slug: String,
+    pub version_id: String,
+    pub version_number: String,
+    pub filename: String,
+    pub url: String,
+    pub sha512: Option<String>,
+}
+
+impl ModLock {
+    /// Parse a lockfile from the given path
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let content = read_config(path.as_ref())?;
+        content.parse()
+    }
+
+    /// Save the lockfile atomically, keeping the previous version as `<file>.bak`,
+    /// with entries sorted by slug for stable diffs
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let mut sorted = self.clone();
+        sorted.mods.sort_by(|a, b| a.slug.cmp(&b.slug));
+        let content = toml::to_string_pretty(&sorted).map_err(ConfigError::Serialize)?;
+        Ok(write_with_backup(path, content)?)
+    }
+
+    /// Load mc.lock from the current directory, or an empty lock if it does not exist.
+    /// A legacy mc.lock holding a server PID is first moved to the PID file.
+    pub fn load() -> Result<Self> {
+        pid_file::migrate_legacy()?;
+        if Path::new("mc.lock").exists() {
+            Self::from_file("mc.lock")
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Look up the locked entry for a slug
+    pub fn get(&self, slug: &str) -> Option<&LockedMod> {
+        self.mods.iter().find(|m| m.slug == slug)
+    }
+
+    /// Insert or replace the entry for a mod
+    pub fn upsert(&mut self, entry: LockedMod) {
+        match self.mods.iter_mut().find(|m| m.slug == entry.slug) {
+            Some(existing) => *existing = entry,
+            None => self.mods.push(entry),
+        }
+    }
+
+    /// Remove the entry for a slug, returning whether one existed
+    pub fn remove(&mut self, slug: &str) -> bool {
+        let before = self.mods.len();
+        self.mods.retain(|m| m.slug != slug);
+        self.mods.len() != before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(slug: &str, version: &str) -> LockedMod {
+        LockedMod {
+            slug: slug.to_string(),
+            version_id: format!("{}-id", version),
+            version_number: version.to_string(),
+            filename: format!("{}-{}.jar", slug, version),
+            url: format!("https://cdn.example/{}-{}.jar", slug, version),
+            sha512: Some("abc".to_string()),
+        }
+    }
+
+    #[test]
+    fn upsert_replaces_and_remove_deletes() {
+        let mut lock = ModLock::default();
+        lock.upsert(entry("sodium", "0.5.3"));
+        lock.upsert(entry("lithium", "0.11.2"));
+        lock.upsert(entry("sodium", "0.5.4"));
+
+        assert_eq!(lock.mods.len(), 2);
+        assert_eq!(lock.get("sodium").unwrap().version_number, "0.5.4");
+
+        assert!(lock.remove("lithium"));
+        assert!(!lock.remove("lithium"));
+        assert!(lock.get("lithium").is_none());
+    }
+
+    #[test]
+    fn round_trip_toml() {
+        let mut lock = ModLock::default();
+        lock.upsert(entry("sodium", "0.5.3"));
+
+        let s = toml::to_string_pretty(&lock).unwrap();
+        assert!(s.contains("[[mods]]"));
+        let parsed = ModLock::from_str(&s).unwrap();
+        assert_eq!(parsed.get("sodium"), lock.get("sodium"));
+    }
+}
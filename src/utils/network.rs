@@ -0,0 +1,140 @@
+// Process-wide switch for the global --offline flag
+use crate::error::Error;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tracing::debug;
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Disable network access for the rest of the process
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
+
+/// Whether --offline was given
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
+}
+
+/// Fail with a clear message if `service` would be contacted under --offline
+pub fn require_online(service: &str) -> Result<(), Error> {
+    if is_offline() {
+        Err(Error::Network(format!(
+            "{} needs network access, which --offline disables",
+            service
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// How long an API request may take, from connecting to reading the whole body
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// HTTP client for the API clients, giving up on requests after `timeout`
+pub fn http_client(user_agent: &str, timeout: Duration) -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent(user_agent)
+        .timeout(timeout)
+        .build()
+}
+
+/// HTTP client for file downloads. Bodies such as server jars can take longer
+/// than `timeout` in total, so only connecting and each read are limited, which
+/// still gives up on a stalled CDN
+pub fn download_client(user_agent: &str, timeout: Duration) -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .user_agent(user_agent)
+        .connect_timeout(timeout)
+        .read_timeout(timeout)
+        .build()
+}
+
+/// Retries after the first attempt for connection errors and 5xx responses
+const MAX_RETRIES: u32 = 3;
+/// Delay before the first retry; doubled for each one after (kept short in tests)
+const INITIAL_BACKOFF: Duration = if cfg!(test) {
+    Duration::from_millis(10)
+} else {
+    Duration::from_millis(500)
+};
+
+/// Send a request, retrying transient failures with exponential backoff.
+/// Requests whose body cannot be cloned are sent once.
+pub async fn send(request: reqwest::RequestBuilder) -> Result<reqwest::Response, Error> {
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 0;
+    let mut request = request;
+    loop {
+        let retry = request.try_clone().filter(|_| attempt < MAX_RETRIES);
+        let result = request.send().await;
+        let transient = match &result {
+            Ok(response) => response.status().is_server_error(),
+            Err(e) => e.is_connect() || e.is_timeout(),
+        };
+        match retry {
+            Some(next) if transient => {
+                attempt += 1;
+                debug!(
+                    "Retrying request ({}/{}) in {}ms",
+                    attempt,
+                    MAX_RETRIES,
+                    backoff.as_millis()
+                );
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                request = next;
+            }
+            _ => return result.map_err(Error::from),
+        }
+    }
+}
+
+/// Tell timeouts and connection failures apart from other request errors, naming
+/// the host involved
+pub fn describe_error(e: &reqwest::Error) -> String {
+    let host = e
+        .url()
+        .and_then(|u| u.host_str())
+        .unwrap_or("the server")
+        .to_string();
+    if e.is_timeout() {
+        format!(
+            "Request to {} timed out. Check your connection or try again later.",
+            host
+        )
+    } else if e.is_connect() {
+        // reqwest's own message is generic; the innermost cause says what failed (DNS, refused, ...)
+        let mut cause: &dyn std::error::Error = e;
+        while let Some(source) = cause.source() {
+            cause = source;
+        }
+        format!("Could not connect to {}: {}", host, cause)
+    } else {
+        e.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::mock_http::serve_sequence;
+
+    #[tokio::test]
+    async fn test_send_retries_server_errors() {
+        let replies = ["503 Service Unavailable", "200 OK"]
+            .iter()
+            .map(|status| {
+                format!(
+                    "HTTP/1.1 {}\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok",
+                    status
+                )
+            })
+            .collect();
+        let url = serve_sequence(replies).await;
+
+        let request = reqwest::Client::new().get(url);
+        let response = send(request).await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+    }
+}
@@ -0,0 +1,36 @@
+// Per-user directories used by mc-cli
+use std::env;
+use std::path::PathBuf;
+
+/// Per-user cache directory for mc-cli, if a home directory can be determined
+pub fn cache_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_CACHE_HOME").filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir).join("mc-cli"));
+    }
+    if cfg!(windows) {
+        return env::var_os("LOCALAPPDATA").map(|d| PathBuf::from(d).join("mc-cli").join("cache"));
+    }
+    env::var_os("HOME").map(|h| PathBuf::from(h).join(".cache").join("mc-cli"))
+}
+
+/// Per-user config directory for mc-cli (`~/.config/mc-cli`, `%APPDATA%\mc-cli` on Windows)
+pub fn config_dir() -> Option<PathBuf> {
+    if let Some(dir) = env::var_os("XDG_CONFIG_HOME").filter(|d| !d.is_empty()) {
+        return Some(PathBuf::from(dir).join("mc-cli"));
+    }
+    if cfg!(windows) {
+        return env::var_os("APPDATA").map(|d| PathBuf::from(d).join("mc-cli"));
+    }
+    env::var_os("HOME").map(|h| PathBuf::from(h).join(".config").join("mc-cli"))
+}
+
+/// Per-user state directory for mc-cli (`~/.mc-cli`), e.g. for console history
+pub fn state_dir() -> Option<PathBuf> {
+    let home = if cfg!(windows) {
+        env::var_os("USERPROFILE")
+    } else {
+        env::var_os("HOME")
+    };
+    home.filter(|h| !h.is_empty())
+        .map(|h| PathBuf::from(h).join(".mc-cli"))
+}
@@ -0,0 +1,113 @@
+// Location of the server PID file shared by run/stop/status
+use crate::utils::process::is_alive;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// File the running server's PID is stored in
+pub const PID_FILE: &str = "mc-server.pid";
+
+/// Start time and launch command of the server in PID_FILE; kept separate so
+/// the PID file stays a bare PID for older readers
+pub const INFO_FILE: &str = "mc-server.json";
+
+/// Older releases stored the PID in mc.lock, which is now the mod lockfile
+const LEGACY_PID_FILE: &str = "mc.lock";
+
+/// Find the PID file to read, falling back to a legacy mc.lock that holds a bare PID
+pub fn locate() -> Option<PathBuf> {
+    let current = PathBuf::from(PID_FILE);
+    if current.exists() {
+        return Some(current);
+    }
+    let legacy = PathBuf::from(LEGACY_PID_FILE);
+    match fs::read_to_string(&legacy) {
+        Ok(content) if is_pid(&content) => Some(legacy),
+        _ => None,
+    }
+}
+
+/// PID from the PID file if that process is still alive
+pub fn running_pid() -> Option<String> {
+    let content = fs::read_to_string(locate()?).ok()?;
+    let pid = content.trim();
+    (!pid.is_empty() && is_alive(pid)).then(|| pid.to_string())
+}
+
+/// Details recorded by `run` when it starts the server
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ServerInfo {
+    pub pid: u32,
+    /// Unix timestamp (seconds) the server was started at
+    pub started_at: u64,
+    pub launch_cmd: Vec<String>,
+}
+
+impl ServerInfo {
+    /// Seconds since the server started
+    pub fn uptime_secs(&self) -> u64 {
+        unix_now().saturating_sub(self.started_at)
+    }
+}
+
+/// Write the PID file and its INFO_FILE for a freshly started server
+pub fn record_start(pid: u32, launch_cmd: &[&str]) -> std::io::Result<()> {
+    fs::write(PID_FILE, format!("{}\n", pid))?;
+    let info = ServerInfo {
+        pid,
+        started_at: unix_now(),
+        launch_cmd: launch_cmd.iter().map(|s| s.to_string()).collect(),
+    };
+    fs::write(INFO_FILE, serde_json::to_string_pretty(&info)?)
+}
+
+/// Start details for `pid`, if `run` recorded them (servers started by older
+/// releases, or by hand, have none)
+pub fn read_info(pid: &str) -> Option<ServerInfo> {
+    let info: ServerInfo = serde_json::from_str(&fs::read_to_string(INFO_FILE).ok()?).ok()?;
+    (info.pid.to_string() == pid).then_some(info)
+}
+
+/// Remove INFO_FILE once the server it describes is gone
+pub fn remove_info() {
+    let _ = fs::remove_file(INFO_FILE);
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// Move a bare PID out of a legacy mc.lock into the PID file so mc.lock can hold the mod lockfile
+pub fn migrate_legacy() -> std::io::Result<()> {
+    let legacy = PathBuf::from(LEGACY_PID_FILE);
+    let content = match fs::read_to_string(&legacy) {
+        Ok(content) if is_pid(&content) => content,
+        _ => return Ok(()),
+    };
+    let current = PathBuf::from(PID_FILE);
+    if !current.exists() {
+        fs::write(&current, content)?;
+    }
+    fs::remove_file(&legacy)
+}
+
+/// Whether file contents look like a PID rather than a TOML lockfile
+fn is_pid(content: &str) -> bool {
+    content.trim().parse::<u32>().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn legacy_pid_detection() {
+        assert!(is_pid("12345\n"));
+        assert!(!is_pid("[[mods]]\nslug = \"sodium\"\n"));
+        assert!(!is_pid(""));
+    }
+}
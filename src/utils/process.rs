@@ -0,0 +1,130 @@
+// Helpers for inspecting OS processes by PID
+use std::process::{Command, Stdio};
+
+/// Build the program and arguments used to check whether a PID exists on the given platform
+pub fn alive_command(pid: &str, windows: bool) -> (&'static str, Vec<String>) {
+    if windows {
+        (
+            "tasklist",
+            vec![
+                "/FI".to_string(),
+                format!("PID eq {}", pid),
+                "/FO".to_string(),
+                "CSV".to_string(),
+                "/NH".to_string(),
+            ],
+        )
+    } else {
+        // `kill -0` performs the permission/existence check without sending a signal
+        ("kill", vec!["-0".to_string(), pid.to_string()])
+    }
+}
+
+/// Whether CSV `tasklist` output lists the PID; tasklist exits 0 even when nothing matches
+fn tasklist_has_pid(output: &str, pid: &str) -> bool {
+    output
+        .lines()
+        .any(|line| line.split(',').nth(1).map(|f| f.trim_matches('"')) == Some(pid))
+}
+
+/// Check whether a process with the given PID currently exists
+pub fn is_alive(pid: &str) -> bool {
+    let windows = cfg!(windows);
+    let (program, args) = alive_command(pid, windows);
+    match Command::new(program)
+        .args(&args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+    {
+        Ok(out) if windows => tasklist_has_pid(&String::from_utf8_lossy(&out.stdout), pid),
+        Ok(out) => out.status.success(),
+        Err(_) => false,
+    }
+}
+
+/// Build the program and arguments used to force-kill a PID on the given platform
+pub fn kill_command(pid: &str, windows: bool) -> (&'static str, Vec<String>) {
+    if windows {
+        (
+            "taskkill",
+            vec!["/PID".to_string(), pid.to_string(), "/F".to_string()],
+        )
+    } else {
+        ("kill", vec!["-9".to_string(), pid.to_string()])
+    }
+}
+
+/// Build the program and arguments used to ask a PID to shut down on the given platform
+pub fn terminate_command(pid: &str, windows: bool) -> (&'static str, Vec<String>) {
+    if windows {
+        ("taskkill", vec!["/PID".to_string(), pid.to_string()])
+    } else {
+        ("kill", vec!["-TERM".to_string(), pid.to_string()])
+    }
+}
+
+/// Ask a process to exit cleanly (SIGTERM runs the JVM shutdown hook, which saves the world)
+pub fn terminate(pid: &str) -> std::io::Result<bool> {
+    let (program, args) = terminate_command(pid, cfg!(windows));
+    let output = Command::new(program).args(&args).output()?;
+    Ok(output.status.success())
+}
+
+/// Force-kill a process, returning whether the kill command succeeded
+pub fn kill(pid: &str) -> std::io::Result<bool> {
+    let (program, args) = kill_command(pid, cfg!(windows));
+    let output = Command::new(program).args(&args).output()?;
+    Ok(output.status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kill_command_unix() {
+        let (program, args) = kill_command("1234", false);
+        assert_eq!(program, "kill");
+        assert_eq!(args, vec!["-9", "1234"]);
+    }
+
+    #[test]
+    fn kill_command_windows() {
+        let (program, args) = kill_command("1234", true);
+        assert_eq!(program, "taskkill");
+        assert_eq!(args, vec!["/PID", "1234", "/F"]);
+    }
+
+    #[test]
+    fn alive_command_per_platform() {
+        let (program, args) = alive_command("1234", false);
+        assert_eq!(program, "kill");
+        assert_eq!(args, vec!["-0", "1234"]);
+
+        let (program, args) = alive_command("1234", true);
+        assert_eq!(program, "tasklist");
+        assert_eq!(args, vec!["/FI", "PID eq 1234", "/FO", "CSV", "/NH"]);
+    }
+
+    #[test]
+    fn parses_tasklist_output() {
+        let running = "\"java.exe\",\"1234\",\"Console\",\"1\",\"812,344 K\"\r\n";
+        assert!(tasklist_has_pid(running, "1234"));
+        assert!(!tasklist_has_pid(running, "123"));
+        let none = "INFO: No tasks are running which match the specified criteria.\r\n";
+        assert!(!tasklist_has_pid(none, "1234"));
+    }
+
+    #[test]
+    fn terminate_command_per_platform() {
+        assert_eq!(
+            terminate_command("1234", false),
+            ("kill", vec!["-TERM".to_string(), "1234".to_string()])
+        );
+        assert_eq!(
+            terminate_command("1234", true),
+            ("taskkill", vec!["/PID".to_string(), "1234".to_string()])
+        );
+    }
+}
@@ -1,48 +1,167 @@
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use crate::error::{Error, Result};
+use crate::utils::mc_server_props::ServerProperties;
+use std::path::Path;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
 use tokio::net::TcpStream;
 
 // Protocol constants from mcrcon reference
 const RCON_EXEC_COMMAND: i32 = 2;
 const RCON_AUTHENTICATE: i32 = 3;
 const RCON_PID: i32 = 0x0badc0de; // arbitrary client id
+const RCON_END_ID: i32 = 0x0badc0df; // sentinel id marking the end of a response
 
 const MIN_PACKET_SIZE: i32 = 10; // size(id + type + empty) + payload
+/// Largest packet accepted by default. The spec caps a response body at 4096
+/// bytes (4110 with the header), but Minecraft splits output into 4096-character
+/// chunks, so this allows for multi-byte UTF-8.
+pub const DEFAULT_MAX_PACKET_SIZE: usize = 4 * 4096 + MIN_PACKET_SIZE as usize;
+
+/// Default time to wait for a server response before giving up
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct RconClient {
     stream: TcpStream,
+    timeout: Duration,
+    max_packet_size: usize,
 }
 
 impl RconClient {
-    pub async fn connect(
+    pub async fn connect(host: &str, port: u16, password: &str) -> Result<Self> {
+        Self::connect_with_timeout(host, port, password, DEFAULT_TIMEOUT).await
+    }
+
+    /// Connect and authenticate, failing any read that takes longer than `timeout`
+    pub async fn connect_with_timeout(
         host: &str,
         port: u16,
         password: &str,
-    ) -> Result<Self, Box<dyn std::error::Error>> {
+        timeout: Duration,
+    ) -> Result<Self> {
         let addr = format!("{}:{}", host, port);
-        let mut stream = TcpStream::connect(addr).await?;
+        let mut stream = tokio::time::timeout(timeout, TcpStream::connect(addr))
+            .await
+            .map_err(|_| Error::Rcon("RCON connection timed out".to_string()))??;
 
         // authenticate
         let auth_packet = build_packet(RCON_PID, RCON_AUTHENTICATE, password);
         send_packet(&mut stream, &auth_packet).await?;
-        let resp = recv_packet(&mut stream).await?;
+        let resp = tokio::time::timeout(timeout, recv_packet(&mut stream, DEFAULT_MAX_PACKET_SIZE))
+            .await
+            .map_err(|_| Error::Rcon("RCON authentication timed out".to_string()))??;
         if resp.id == -1 {
-            return Err("Authentication failed".into());
+            return Err(Error::Rcon("Authentication failed".to_string()));
         }
 
-        Ok(Self { stream })
+        Ok(Self {
+            stream,
+            timeout,
+            max_packet_size: DEFAULT_MAX_PACKET_SIZE,
+        })
     }
 
-    pub async fn cmd(&mut self, command: &str) -> Result<String, Box<dyn std::error::Error>> {
+    /// Override the largest response packet accepted (default [`DEFAULT_MAX_PACKET_SIZE`])
+    pub fn with_max_packet_size(mut self, max_packet_size: usize) -> Self {
+        self.max_packet_size = max_packet_size;
+        self
+    }
+
+    pub async fn cmd(&mut self, command: &str) -> Result<String> {
         let packet = build_packet(RCON_PID, RCON_EXEC_COMMAND, command);
         send_packet(&mut self.stream, &packet).await?;
-        let resp = recv_packet(&mut self.stream).await?;
-        if resp.id != RCON_PID {
-            return Err("Invalid response id".into());
+
+        // Large outputs are split across several packets. The server answers
+        // requests in order, so an empty command sent with a sentinel id right
+        // after the real one marks where the real response ends.
+        let sentinel = build_packet(RCON_END_ID, RCON_EXEC_COMMAND, "");
+        send_packet(&mut self.stream, &sentinel).await?;
+
+        let mut output = String::new();
+        loop {
+            let resp = tokio::time::timeout(
+                self.timeout,
+                recv_packet(&mut self.stream, self.max_packet_size),
+            )
+            .await
+            .map_err(|_| Error::Rcon("RCON read timed out".to_string()))??;
+            if resp.id == RCON_END_ID {
+                break;
+            }
+            if resp.id != RCON_PID {
+                return Err(Error::Rcon("Invalid response id".to_string()));
+            }
+            output.push_str(&resp.payload);
         }
-        Ok(resp.payload)
+        Ok(output)
     }
 }
 
+const DEFAULT_HOST: &str = "127.0.0.1";
+const DEFAULT_PORT: u16 = 25575;
+
+/// Resolve RCON host, port and password. Each value comes from the first of:
+/// the `RCON_HOST`/`RCON_PORT`/`RCON_PASSWORD` env vars, the server.properties
+/// file at `path` (`rcon.host` etc.), then the defaults 127.0.0.1:25575 and no password.
+pub fn resolve_rcon_config<P: AsRef<Path>>(path: P) -> (String, u16, String) {
+    resolve_with_env(path, |key| std::env::var(key).ok())
+}
+
+fn resolve_with_env<P: AsRef<Path>>(
+    path: P,
+    env: impl Fn(&str) -> Option<String>,
+) -> (String, u16, String) {
+    let props = ServerProperties::from_file(path).ok();
+    let lookup = |env_key: &str, keys: [&str; 2]| {
+        env(env_key).filter(|v| !v.is_empty()).or_else(|| {
+            let p = props.as_ref()?;
+            p.get(keys[0]).or_else(|| p.get(keys[1]))
+        })
+    };
+
+    let host = lookup("RCON_HOST", ["rcon.host", "rcon_host"])
+        .filter(|h| !h.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_HOST.to_string());
+    let port = lookup("RCON_PORT", ["rcon.port", "rcon_port"])
+        .and_then(|p| p.trim().parse::<u16>().ok())
+        .unwrap_or(DEFAULT_PORT);
+    let password = lookup("RCON_PASSWORD", ["rcon.password", "rcon_password"]).unwrap_or_default();
+    (host, port, password)
+}
+
+/// Connect with [`resolve_rcon_config`] for ./server.properties, run one command
+/// and return the server's reply
+pub async fn send_command(command: &str) -> Result<String> {
+    let (host, port, password) = resolve_rcon_config("server.properties");
+    let mut client = RconClient::connect(&host, port, &password).await?;
+    client.cmd(command).await
+}
+
+/// Online/max player counts and names parsed from the reply to `list`
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlayerList {
+    pub online: u32,
+    pub max: u32,
+    pub names: Vec<String>,
+}
+
+/// Parse `list` output, e.g. "There are 2 of a max of 20 players online: a, b"
+/// (or the older "There are 2/20 players online:a, b")
+pub fn parse_player_list(reply: &str) -> Option<PlayerList> {
+    let (counts, names) = reply.split_once(':')?;
+    let mut numbers = counts
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|s| !s.is_empty())
+        .filter_map(|s| s.parse::<u32>().ok());
+    let online = numbers.next()?;
+    let max = numbers.next()?;
+    let names = names
+        .split(',')
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .collect();
+    Some(PlayerList { online, max, names })
+}
+
 struct Packet {
     size: i32,
     id: i32,
@@ -62,10 +181,7 @@ fn build_packet(id: i32, kind: i32, payload: &str) -> Packet {
     }
 }
 
-async fn send_packet(
-    stream: &mut TcpStream,
-    packet: &Packet,
-) -> Result<(), Box<dyn std::error::Error>> {
+async fn send_packet(stream: &mut TcpStream, packet: &Packet) -> Result<()> {
     let mut buf = Vec::with_capacity((packet.size + 4) as usize);
     buf.extend_from_slice(&packet.size.to_le_bytes());
     buf.extend_from_slice(&packet.id.to_le_bytes());
@@ -77,25 +193,34 @@ async fn send_packet(
     Ok(())
 }
 
-async fn recv_packet(stream: &mut TcpStream) -> Result<Packet, Box<dyn std::error::Error>> {
+async fn recv_packet<R: AsyncRead + Unpin>(stream: &mut R, max_size: usize) -> Result<Packet> {
     let mut size_le = [0u8; 4];
     stream.read_exact(&mut size_le).await?;
     let size = i32::from_le_bytes(size_le);
-    if !(MIN_PACKET_SIZE..=4096).contains(&size) {
-        return Err("Invalid packet size".into());
+    if size < MIN_PACKET_SIZE {
+        return Err(Error::Rcon(format!(
+            "RCON packet size {} is below the {}-byte minimum",
+            size, MIN_PACKET_SIZE
+        )));
+    }
+    if size as usize > max_size {
+        return Err(Error::Rcon(format!(
+            "RCON packet of {} bytes exceeds the {}-byte limit",
+            size, max_size
+        )));
     }
 
     let mut rest = vec![0u8; size as usize];
     stream.read_exact(&mut rest).await?;
 
     if rest.len() < 8 {
-        return Err("Short packet".into());
+        return Err(Error::Rcon("Short packet".to_string()));
     }
     let id = i32::from_le_bytes(rest[0..4].try_into().unwrap());
     let kind = i32::from_le_bytes(rest[4..8].try_into().unwrap());
     // payload is until last two null bytes
     if rest.len() < 10 {
-        return Err("Short payload".into());
+        return Err(Error::Rcon("Short payload".to_string()));
     }
     // strip last two nulls
     let payload_bytes = &rest[8..rest.len() - 2];
@@ -108,3 +233,235 @@ async fn recv_packet(stream: &mut TcpStream) -> Result<Packet, Box<dyn std::erro
         payload,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    /// Password the fake server accepts
+    const PASSWORD: &str = "pw";
+
+    /// Accept one client, answer its auth packet (id -1 unless the password is
+    /// [`PASSWORD`]) and hand the stream to `respond`
+    async fn fake_server<F, Fut>(respond: F) -> u16
+    where
+        F: FnOnce(TcpStream) -> Fut + Send + 'static,
+        Fut: std::future::Future<Output = ()> + Send,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let auth = recv_packet(&mut stream, DEFAULT_MAX_PACKET_SIZE)
+                .await
+                .unwrap();
+            let id = if auth.payload == PASSWORD {
+                auth.id
+            } else {
+                -1
+            };
+            let reply = build_packet(id, 2, "");
+            send_packet(&mut stream, &reply).await.unwrap();
+            respond(stream).await;
+        });
+        port
+    }
+
+    #[test]
+    fn rcon_config_resolution_order() {
+        let props =
+            std::env::temp_dir().join(format!("mc-cli_rcon_{}.properties", std::process::id()));
+        std::fs::write(&props, "rcon.port=25580\nrcon.password=fromfile\n").unwrap();
+
+        let no_env = |_: &str| None;
+        assert_eq!(
+            resolve_with_env(&props, no_env),
+            ("127.0.0.1".to_string(), 25580, "fromfile".to_string())
+        );
+
+        let env = |key: &str| (key == "RCON_PASSWORD").then(|| "fromenv".to_string());
+        assert_eq!(resolve_with_env(&props, env).2, "fromenv");
+
+        std::fs::remove_file(&props).unwrap();
+        assert_eq!(
+            resolve_with_env(&props, no_env),
+            ("127.0.0.1".to_string(), 25575, String::new())
+        );
+    }
+
+    #[test]
+    fn parses_player_list() {
+        assert_eq!(
+            parse_player_list("There are 2 of a max of 20 players online: alice, bob"),
+            Some(PlayerList {
+                online: 2,
+                max: 20,
+                names: vec!["alice".to_string(), "bob".to_string()],
+            })
+        );
+        let empty = parse_player_list("There are 0/10 players online:").unwrap();
+        assert_eq!((empty.online, empty.max, empty.names.len()), (0, 10, 0));
+        assert_eq!(parse_player_list("Unknown command"), None);
+    }
+
+    #[tokio::test]
+    async fn reassembles_full_size_fragments() {
+        let chunk = "a".repeat(4096);
+        let expected = format!("{}bc", chunk);
+        let port = fake_server(move |mut stream| async move {
+            let command = recv_packet(&mut stream, DEFAULT_MAX_PACKET_SIZE)
+                .await
+                .unwrap();
+            let sentinel = recv_packet(&mut stream, DEFAULT_MAX_PACKET_SIZE)
+                .await
+                .unwrap();
+            for part in [chunk.as_str(), "bc"] {
+                let packet = build_packet(command.id, 0, part);
+                send_packet(&mut stream, &packet).await.unwrap();
+            }
+            let end = build_packet(sentinel.id, 0, "");
+            send_packet(&mut stream, &end).await.unwrap();
+        })
+        .await;
+
+        let mut client = RconClient::connect("127.0.0.1", port, PASSWORD)
+            .await
+            .unwrap();
+        assert_eq!(client.cmd("list").await.unwrap(), expected);
+    }
+
+    /// A raw packet: little-endian size, id and type, then the payload and two nulls
+    fn encode(size: i32, id: i32, kind: i32, payload: &[u8]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&size.to_le_bytes());
+        buf.extend_from_slice(&id.to_le_bytes());
+        buf.extend_from_slice(&kind.to_le_bytes());
+        buf.extend_from_slice(payload);
+        buf.extend_from_slice(&[0, 0]);
+        buf
+    }
+
+    #[tokio::test]
+    async fn packets_round_trip_in_wire_format() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let sent = build_packet(42, RCON_EXEC_COMMAND, "say hi");
+        tokio::spawn(async move {
+            let mut client = TcpStream::connect(addr).await.unwrap();
+            send_packet(&mut client, &sent).await.unwrap();
+            send_packet(&mut client, &build_packet(43, RCON_EXEC_COMMAND, "list"))
+                .await
+                .unwrap();
+        });
+        let (mut server, _) = listener.accept().await.unwrap();
+
+        let mut raw = vec![0u8; 4 + 10 + "say hi".len()];
+        server.read_exact(&mut raw).await.unwrap();
+        assert_eq!(raw, encode(16, 42, RCON_EXEC_COMMAND, b"say hi"));
+
+        let packet = recv_packet(&mut server, DEFAULT_MAX_PACKET_SIZE)
+            .await
+            .unwrap();
+        assert_eq!(packet.size, 14);
+        assert_eq!(packet.id, 43);
+        assert_eq!(packet.kind, RCON_EXEC_COMMAND);
+        assert_eq!(packet.payload, "list");
+    }
+
+    #[tokio::test]
+    async fn authenticates_and_runs_a_command() {
+        let port = fake_server(|mut stream| async move {
+            let command = recv_packet(&mut stream, DEFAULT_MAX_PACKET_SIZE)
+                .await
+                .unwrap();
+            let sentinel = recv_packet(&mut stream, DEFAULT_MAX_PACKET_SIZE)
+                .await
+                .unwrap();
+            assert_eq!(command.kind, RCON_EXEC_COMMAND);
+            assert_eq!(command.payload, "list");
+            let reply = build_packet(command.id, 0, "There are 0 of a max of 20 players online: ");
+            send_packet(&mut stream, &reply).await.unwrap();
+            send_packet(&mut stream, &build_packet(sentinel.id, 0, ""))
+                .await
+                .unwrap();
+        })
+        .await;
+
+        let mut client = RconClient::connect("127.0.0.1", port, PASSWORD)
+            .await
+            .unwrap();
+        assert_eq!(
+            client.cmd("list").await.unwrap(),
+            "There are 0 of a max of 20 players online: "
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_a_wrong_password() {
+        let port = fake_server(|_| async {}).await;
+
+        let err = RconClient::connect("127.0.0.1", port, "wrong")
+            .await
+            .err()
+            .unwrap();
+        assert!(matches!(err, Error::Rcon(_)));
+        assert_eq!(err.to_string(), "Authentication failed");
+    }
+
+    #[tokio::test]
+    async fn rejects_a_short_packet() {
+        // A size of 8 leaves no room for the two trailing nulls
+        let buf = encode(8, 1, 0, b"");
+        let err = recv_packet(&mut buf.as_slice(), DEFAULT_MAX_PACKET_SIZE)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Rcon(_)));
+        assert_eq!(
+            err.to_string(),
+            "RCON packet size 8 is below the 10-byte minimum"
+        );
+    }
+
+    #[tokio::test]
+    async fn decodes_max_size_packet() {
+        let payload = vec![b'x'; DEFAULT_MAX_PACKET_SIZE - MIN_PACKET_SIZE as usize];
+        let buf = encode(DEFAULT_MAX_PACKET_SIZE as i32, 7, 0, &payload);
+
+        let packet = recv_packet(&mut buf.as_slice(), DEFAULT_MAX_PACKET_SIZE)
+            .await
+            .unwrap();
+        assert_eq!(packet.id, 7);
+        assert_eq!(packet.payload.len(), payload.len());
+
+        let err = recv_packet(&mut buf.as_slice(), DEFAULT_MAX_PACKET_SIZE - 1)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::Rcon(_)));
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "RCON packet of {} bytes exceeds the {}-byte limit",
+                DEFAULT_MAX_PACKET_SIZE,
+                DEFAULT_MAX_PACKET_SIZE - 1
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn times_out_when_server_is_silent() {
+        let port = fake_server(|mut stream| async move {
+            let _ = recv_packet(&mut stream, DEFAULT_MAX_PACKET_SIZE).await;
+            tokio::time::sleep(Duration::from_secs(2)).await;
+        })
+        .await;
+
+        let timeout = Duration::from_millis(100);
+        let mut client = RconClient::connect_with_timeout("127.0.0.1", port, PASSWORD, timeout)
+            .await
+            .unwrap();
+        let err = client.cmd("list").await.unwrap_err();
+        assert!(matches!(err, Error::Rcon(_)));
+        assert_eq!(err.to_string(), "RCON read timed out");
+    }
+}
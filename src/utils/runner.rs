@@ -1,5 +1,6 @@
 // Create a new process to run the server and return a handle
 use std::process::{Child, Command};
+use tracing::{debug, info};
 
 pub async fn run_cmd(cmd_args: &[&str]) -> Result<Child, Box<dyn std::error::Error>> {
     run_cmd_with_io(cmd_args, true).await
@@ -9,6 +10,7 @@ pub async fn run_cmd_with_io(
     cmd_args: &[&str],
     inherit_stdio: bool,
 ) -> Result<Child, Box<dyn std::error::Error>> {
+    debug!("Launching: {}", cmd_args.join(" "));
     let mut cmd = Command::new(cmd_args[0]);
     cmd.args(&cmd_args[1..]);
 
@@ -21,7 +23,7 @@ pub async fn run_cmd_with_io(
     }
 
     let child = cmd.spawn()?;
-    println!("Command started successfully with PID: {}", child.id());
+    info!("Command started successfully with PID: {}", child.id());
 
     // return process handle
     Ok(child)
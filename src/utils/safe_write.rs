@@ -0,0 +1,107 @@
+// Crash-safe file replacement used when saving config files
+use std::ffi::OsString;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Sibling of `path` with `suffix` appended to its file name, e.g. mc.toml -> mc.toml.bak
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().map(OsString::from).unwrap_or_default();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Replace a file atomically: write a `.tmp` sibling and rename it over the target,
+/// keeping the previous contents in a single `.bak` copy
+pub fn write_with_backup<P: AsRef<Path>>(path: P, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let path = path.as_ref();
+    let tmp = sibling(path, ".tmp");
+    fs::write(&tmp, contents)?;
+
+    if path.exists()
+        && let Err(e) = fs::copy(path, sibling(path, ".bak"))
+    {
+        let _ = fs::remove_file(&tmp);
+        return Err(e);
+    }
+    fs::rename(&tmp, path)
+}
+
+/// Replace a file atomically for contents that must stay private: the file is
+/// readable only by its owner on Unix and no `.bak` copy is kept (a stale one
+/// from an earlier write is removed)
+pub fn write_private<P: AsRef<Path>>(path: P, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    let path = path.as_ref();
+    let tmp = sibling(path, ".tmp");
+    let _ = fs::remove_file(&tmp);
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let written = options.open(&tmp).and_then(|mut file| {
+        file.write_all(contents.as_ref())?;
+        file.sync_all()
+    });
+    if let Err(e) = written {
+        let _ = fs::remove_file(&tmp);
+        return Err(e);
+    }
+    fs::rename(&tmp, path)?;
+
+    let backup = sibling(path, ".bak");
+    if backup.exists() {
+        fs::remove_file(backup)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_previous_contents_as_backup() {
+        let dir = std::env::temp_dir().join(format!("mc-cli_safe_write_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("mc.toml");
+
+        write_with_backup(&target, "first").unwrap();
+        assert!(!dir.join("mc.toml.bak").exists());
+
+        write_with_backup(&target, "second").unwrap();
+        assert_eq!(fs::read_to_string(&target).unwrap(), "second");
+        assert_eq!(
+            fs::read_to_string(dir.join("mc.toml.bak")).unwrap(),
+            "first"
+        );
+        assert!(!dir.join("mc.toml.tmp").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn private_write_keeps_no_backup() {
+        let dir = std::env::temp_dir().join(format!("mc-cli_private_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("config.toml");
+        fs::write(dir.join("config.toml.bak"), "old secret").unwrap();
+
+        write_private(&target, "first").unwrap();
+        write_private(&target, "second").unwrap();
+        assert_eq!(fs::read_to_string(&target).unwrap(), "second");
+        assert!(!dir.join("config.toml.bak").exists());
+        assert!(!dir.join("config.toml.tmp").exists());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(&target).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
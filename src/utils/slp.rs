@@ -0,0 +1,172 @@
+// Minimal client for the Minecraft Server List Ping (status) protocol
+use serde_json::Value;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+/// Protocol version sent in the handshake; -1 asks the server to answer regardless
+const PROTOCOL_VERSION: i32 = -1;
+const NEXT_STATE_STATUS: i32 = 1;
+/// Upper bound on a status response, far above what vanilla servers send
+const MAX_RESPONSE_LEN: i32 = 1 << 20;
+
+/// Parsed subset of a status response
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServerStatus {
+    pub version: String,
+    pub players_online: u64,
+    pub players_max: u64,
+    pub motd: String,
+}
+
+/// Connect to the game port and perform a handshake plus status request
+pub async fn query(
+    host: &str,
+    port: u16,
+    timeout: Duration,
+) -> Result<ServerStatus, Box<dyn std::error::Error>> {
+    tokio::time::timeout(timeout, query_inner(host, port))
+        .await
+        .map_err(|_| "Server list ping timed out")?
+}
+
+async fn query_inner(host: &str, port: u16) -> Result<ServerStatus, Box<dyn std::error::Error>> {
+    let mut stream = TcpStream::connect(format!("{}:{}", host, port)).await?;
+
+    // Handshake: id 0x00, protocol, host, port, next state
+    let mut handshake = Vec::new();
+    write_varint(&mut handshake, 0x00);
+    write_varint(&mut handshake, PROTOCOL_VERSION);
+    write_varint(&mut handshake, host.len() as i32);
+    handshake.extend_from_slice(host.as_bytes());
+    handshake.extend_from_slice(&port.to_be_bytes());
+    write_varint(&mut handshake, NEXT_STATE_STATUS);
+    send_frame(&mut stream, &handshake).await?;
+
+    // Status request: id 0x00 with no fields
+    send_frame(&mut stream, &[0x00]).await?;
+
+    let length = read_varint(&mut stream).await?;
+    if !(1..=MAX_RESPONSE_LEN).contains(&length) {
+        return Err("Invalid status response length".into());
+    }
+    let mut body = vec![0u8; length as usize];
+    stream.read_exact(&mut body).await?;
+
+    let mut cursor = &body[..];
+    if read_varint(&mut cursor).await? != 0x00 {
+        return Err("Unexpected status response packet".into());
+    }
+    let json_len = read_varint(&mut cursor).await?;
+    if json_len < 0 || json_len as usize > cursor.len() {
+        return Err("Invalid status response payload".into());
+    }
+    let json: Value = serde_json::from_slice(&cursor[..json_len as usize])?;
+    Ok(parse_status(&json))
+}
+
+/// Extract the interesting fields from the status JSON
+pub fn parse_status(json: &Value) -> ServerStatus {
+    ServerStatus {
+        version: json["version"]["name"]
+            .as_str()
+            .unwrap_or("unknown")
+            .to_string(),
+        players_online: json["players"]["online"].as_u64().unwrap_or(0),
+        players_max: json["players"]["max"].as_u64().unwrap_or(0),
+        motd: flatten_text(&json["description"]),
+    }
+}
+
+/// Flatten a chat component (plain string, or object with `text` and `extra`) to text
+fn flatten_text(component: &Value) -> String {
+    match component {
+        Value::String(s) => s.clone(),
+        Value::Array(parts) => parts.iter().map(flatten_text).collect(),
+        Value::Object(obj) => {
+            let mut out = obj
+                .get("text")
+                .and_then(|t| t.as_str())
+                .unwrap_or_default()
+                .to_string();
+            if let Some(extra) = obj.get("extra") {
+                out.push_str(&flatten_text(extra));
+            }
+            out
+        }
+        _ => String::new(),
+    }
+}
+
+/// Send a length-prefixed packet
+async fn send_frame(stream: &mut TcpStream, payload: &[u8]) -> std::io::Result<()> {
+    let mut frame = Vec::with_capacity(payload.len() + 5);
+    write_varint(&mut frame, payload.len() as i32);
+    frame.extend_from_slice(payload);
+    stream.write_all(&frame).await
+}
+
+/// Append a protocol VarInt (7 bits per byte, continuation bit set on all but the last)
+fn write_varint(buf: &mut Vec<u8>, value: i32) {
+    let mut v = value as u32;
+    loop {
+        if v & !0x7f == 0 {
+            buf.push(v as u8);
+            return;
+        }
+        buf.push((v & 0x7f) as u8 | 0x80);
+        v >>= 7;
+    }
+}
+
+/// Read a protocol VarInt of at most 5 bytes
+async fn read_varint<R: AsyncRead + Unpin>(
+    reader: &mut R,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    let mut value: u32 = 0;
+    for i in 0..5 {
+        let byte = reader.read_u8().await?;
+        value |= ((byte & 0x7f) as u32) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok(value as i32);
+        }
+    }
+    Err("VarInt is too long".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn varint_round_trip() {
+        for value in [0, 1, 127, 128, 25565, i32::MAX, -1] {
+            let mut buf = Vec::new();
+            write_varint(&mut buf, value);
+            assert_eq!(read_varint(&mut &buf[..]).await.unwrap(), value);
+        }
+        let mut buf = Vec::new();
+        write_varint(&mut buf, 300);
+        assert_eq!(buf, vec![0xac, 0x02]);
+    }
+
+    #[test]
+    fn parses_status_json() {
+        let status = parse_status(&json!({
+            "version": {"name": "1.20.1", "protocol": 763},
+            "players": {"max": 20, "online": 3},
+            "description": {"text": "A ", "extra": [{"text": "server"}, "!"]}
+        }));
+        assert_eq!(
+            status,
+            ServerStatus {
+                version: "1.20.1".to_string(),
+                players_online: 3,
+                players_max: 20,
+                motd: "A server!".to_string(),
+            }
+        );
+        assert_eq!(parse_status(&json!({"description": "plain"})).motd, "plain");
+    }
+}
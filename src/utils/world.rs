@@ -0,0 +1,85 @@
+// World directory and backup archive locations shared by backup/restore
+use crate::utils::mc_server_props::ServerProperties;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Directory backup archives are written to
+pub const BACKUP_DIR: &str = "backups";
+const BACKUP_EXT: &str = ".tar.gz";
+
+/// World directory name from server.properties `level-name`, default `world`
+pub fn level_name() -> String {
+    ServerProperties::from_file("server.properties")
+        .ok()
+        .and_then(|p| p.get("level-name"))
+        .map(|n| n.trim().to_string())
+        .filter(|n| !n.is_empty())
+        .unwrap_or_else(|| "world".to_string())
+}
+
+/// Backup archives in BACKUP_DIR, oldest first (names sort chronologically)
+pub fn list_backups() -> io::Result<Vec<PathBuf>> {
+    let dir = PathBuf::from(BACKUP_DIR);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut backups: Vec<PathBuf> = fs::read_dir(dir)?
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| {
+            p.file_name()
+                .is_some_and(|n| n.to_string_lossy().ends_with(BACKUP_EXT))
+        })
+        .collect();
+    backups.sort();
+    Ok(backups)
+}
+
+/// Path for a new backup taken at `time`
+pub fn backup_path(time: SystemTime) -> PathBuf {
+    PathBuf::from(BACKUP_DIR).join(format!("{}{}", timestamp(time), BACKUP_EXT))
+}
+
+/// UTC timestamp as `YYYYMMDD-HHMMSS`, which sorts chronologically
+pub fn timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let (days, rem) = (secs / 86_400, secs % 86_400);
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn formats_utc_timestamps() {
+        assert_eq!(timestamp(UNIX_EPOCH), "19700101-000000");
+        let leap_day = UNIX_EPOCH + Duration::from_secs(1_709_210_096);
+        assert_eq!(timestamp(leap_day), "20240229-123456");
+    }
+}